@@ -0,0 +1,88 @@
+use altius_benchtools::profiler;
+use std::{env, thread, time::Instant};
+
+/// Records `tx_per_thread` transaction and commit events on each of `thread_num` threads.
+///
+/// Every transaction carries a status and a few notes, similar to what an executor
+/// records when profiling a block.
+fn record_transactions(thread_num: usize, tx_per_thread: usize) {
+    let mut handles = vec![];
+    for thread_id in 0..thread_num {
+        let handle = thread::spawn(move || {
+            for tx_id in 0..tx_per_thread {
+                let tx = format!("0x{:064x}", thread_id * tx_per_thread + tx_id);
+                profiler::start(&tx);
+                profiler::note_str(&tx, "type", "transaction");
+                profiler::note_str(&tx, "status", "success");
+                profiler::note_str(&tx, "from", "0x00000000000000000000000000000000000000aa");
+                profiler::note(&tx, "gas_used", (21000 + tx_id as u64).into());
+                profiler::end(&tx);
+
+                let commit = format!("commit-{}", tx);
+                profiler::start(&commit);
+                profiler::note_str(&commit, "type", "commit");
+                profiler::note_str(&commit, "tx", &tx);
+                profiler::end(&commit);
+            }
+        });
+        handles.push(handle);
+    }
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
+
+/// Runs `f` and returns its result together with the elapsed milliseconds
+fn timed<T>(f: impl FnOnce() -> T) -> (T, f64) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed().as_secs_f64() * 1000.0)
+}
+
+/// Compares the size and the encode/decode speed of the JSON and binary dump formats.
+///
+/// Usage: `cargo run --release --example dump_format_benchmark [events]`
+/// (default: 1,000,000 events, half transactions and half commits, on 8 threads).
+fn main() {
+    let events: usize = env::args()
+        .nth(1)
+        .map(|arg| arg.parse().expect("events must be a number"))
+        .unwrap_or(1_000_000);
+    let thread_num = 8;
+    record_transactions(thread_num, events / thread_num / 2);
+
+    let (json, json_write) = timed(profiler::dump);
+    profiler::clear();
+    let json_size = json.len();
+    let (dump, json_read) = timed(|| serde_json::from_str::<serde_json::Value>(&json).unwrap());
+    drop(json);
+    let (compact, compact_write) = timed(|| serde_json::to_string(&dump).unwrap());
+    let (_, compact_read) = timed(|| serde_json::from_str::<serde_json::Value>(&compact).unwrap());
+    let compact_size = compact.len();
+    drop(compact);
+    let (binary, binary_write) = timed(|| profiler::to_binary(&dump));
+    let (decoded, binary_read) = timed(|| profiler::from_binary(&binary).unwrap());
+    assert_eq!(decoded, dump);
+
+    let mb = |len: usize| len as f64 / 1_000_000.0;
+    println!("{} events on {} threads", events, thread_num);
+    println!("format           size (MB)   encode (ms)   decode (ms)");
+    println!(
+        "json (pretty)   {:>10.1}   {:>11.0}   {:>11.0}",
+        mb(json_size),
+        json_write,
+        json_read
+    );
+    println!(
+        "json (compact)  {:>10.1}   {:>11.0}   {:>11.0}",
+        mb(compact_size),
+        compact_write,
+        compact_read
+    );
+    println!(
+        "binary          {:>10.1}   {:>11.0}   {:>11.0}",
+        mb(binary.len()),
+        binary_write,
+        binary_read
+    );
+}
@@ -1,7 +1,22 @@
-use altius_benchtools::TransactionGenerator;
-use clap::{Arg, Command};
+use altius_benchtools::{
+    sign_transaction, BatchConfig, BlobConfig, DeployConfig, FeeConfig, Interleaving, MixComponent,
+    SetCodeConfig, SkewConfig, StorageConfig, TransactionGenerator,
+};
+use clap::{Arg, ArgMatches, Command};
 use serde_json::{json, Map, Value};
-use std::{fs::File, io::Write};
+use std::{fs::File, io::Write, path::Path};
+
+/// Cancun, or Prague when the test case holds EIP-7702 transactions
+fn default_fork(transactions: &[Value]) -> &'static str {
+    if transactions
+        .iter()
+        .any(|tx| tx.get("authorizationList").is_some())
+    {
+        "Prague"
+    } else {
+        "Cancun"
+    }
+}
 
 pub fn build_json_output(
     pre: Map<String, Value>,
@@ -9,6 +24,7 @@ pub fn build_json_output(
     info: serde_json::Value,
     env: serde_json::Value,
     name: String,
+    fork: &str,
 ) -> Result<Value, Box<dyn std::error::Error>> {
     Ok(json!({
         name: {
@@ -17,7 +33,7 @@ pub fn build_json_output(
             "pre": pre,
             "transaction": transactions,
             "post": {
-                "Cancun": {
+                fork: {
                     "hash": "",
                     "indexes": {},
                     "logs": "",
@@ -28,6 +44,40 @@ pub fn build_json_output(
     }))
 }
 
+/// Signs the transactions for `chain_id`, keeping the `pre` state they execute on
+fn build_raw_output(
+    pre: Map<String, Value>,
+    transactions: &[Value],
+    chain_id: u64,
+) -> Result<Value, Box<dyn std::error::Error>> {
+    let transactions = transactions
+        .iter()
+        .map(|tx| sign_transaction(tx, chain_id))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(json!({
+        "chainId": chain_id,
+        "pre": pre,
+        "transactions": transactions,
+    }))
+}
+
+/// Builds the test case of a `pattern` subcommand, or its raw signed transactions with
+/// `--raw`; the fork defaults to [`default_fork`]
+fn build_pattern_output(
+    sub_m: &ArgMatches,
+    (pre, transactions): PatternData,
+    env: Value,
+    name: String,
+    fork: Option<&str>,
+) -> Result<Value, Box<dyn std::error::Error>> {
+    if *sub_m.get_one::<bool>("raw").unwrap_or(&false) {
+        let chain_id = sub_m.get_one::<String>("chain_id").unwrap().parse()?;
+        return build_raw_output(pre, &transactions, chain_id);
+    }
+    let fork = fork.unwrap_or(default_fork(&transactions));
+    build_json_output(pre, transactions, get_info(), env, name, fork)
+}
+
 fn get_info() -> serde_json::Value {
     json!({
         "comment": "altius transfer",
@@ -54,6 +104,524 @@ fn gen_env() -> serde_json::Value {
     })
 }
 
+/// The `pre` state and the transactions of a test case
+type PatternData = (Map<String, Value>, Vec<Value>);
+
+fn pattern_command() -> Command {
+    Command::new("pattern")
+        .about("Generate transactions based on a pattern")
+        .long_about("Generates transactions following a specific pattern. Available patterns are:\n\n\
+                     - one-to-many: One sender, multiple receivers\n\
+                     \t            o                              \n\
+                     \t    ┌---┬---┼---┬---┐                      \n\
+                     \t    ↓   ↓   ↓   ↓   ↓                      \n\
+                     \t    o   o   o   o   o                      \n\n\
+                     - many-to-many: Multiple senders, multiple receivers\n\
+                     \t    o   o   o   o   o                      \n\
+                     \t    ↓   ↓   ↓   ↓   ↓                      \n\
+                     \t    o   o   o   o   o                      \n\n\
+                     - many-to-one: Multiple senders, one receiver\n\
+                     \t    o   o   o   o   o                      \n\
+                     \t    ↓   ↓   ↓   ↓   ↓                      \n\
+                     \t    └---┴---┼---┴---┘                      \n\
+                     \t            o                              \n\n\
+                     - chained: Transactions form a circular pattern\n\
+                     \t    o → o → o → o → o                      \n\
+                     \t    ↑               ↓                      \n\
+                     \t    o ← o ← o ← o ← o                      \n\n\
+                     - self-to-self: Accounts transfer to themselves\n\
+                     \t    o   o   o   o   o                      \n\
+                     \t    ↓   ↓   ↓   ↓   ↓                      \n\
+                     \t    o   o   o   o   o                      \n\n\
+                     - zipf: Senders and receivers drawn from a pool of accounts with Zipf-distributed\n\
+                     \t    popularity (see --accounts, --zipf-exponent)\n\n\
+                     - hot-spot: Most senders and receivers drawn from a small hot set of the account\n\
+                     \t    pool (see --accounts, --hot-fraction, --hot-traffic)\n\n\
+                     - amm: Uniswap-V2-style swaps on pre-deployed token pairs, the pair of every swap\n\
+                     \t    drawn with Zipf-distributed popularity (see --pairs, --zipf-exponent, --accounts)\n\n\
+                     - nft-mint: Every transaction mints a token of one ERC-721 contract (see --accounts)\n\n\
+                     - nft-transfer: ERC-721 transfers of random tokens from their current owner to random\n\
+                     \t    accounts, each account owning one token at first (see --accounts)\n\n\
+                     - nft-approve: As nft-transfer, but each transfer is an approval followed by a\n\
+                     \t    transferFrom by the approved account\n\n\
+                     - erc1155-batch: ERC-1155 safeBatchTransferFrom calls between random accounts, each\n\
+                     \t    moving a batch of token ids (see --batch-size, --id-overlap, --accounts)\n\n\
+                     - deploy: Contract-creation transactions deploying a contract template\n\
+                     \t    (see --template, --code-size, --accounts)\n\n\
+                     - create2: Deployments of a contract template through a CREATE2 factory\n\
+                     \t    (see --template, --code-size, --accounts)\n\n\
+                     - storage: Reads and writes of storage slots of a synthetic contract\n\
+                     \t    (see --slots, --write-ratio, --key-overlap, --accounts)\n\n\
+                     - blob: EIP-4844 transactions carrying random blobs with locally computed KZG\n\
+                     \t    commitments, needs the `blob` feature (see --blobs, --blob-fee, --blob-sidecars,\n\
+                     \t    --accounts)\n\n\
+                     - set-code: EIP-7702 transactions delegating accounts to a storage contract, followed\n\
+                     \t    by calls into the delegated accounts (see --authorizations, --call-ratio, --accounts)\n\n\
+                     - mix: Several of the patterns above composed into one test case sharing a single\n\
+                     \t    pre state, in proportion to their weights (see --mix, --interleave)\n\
+                     ")
+        .arg(Arg::new("type")
+            .short('y')
+            .long("type")
+            .value_name("TYPE")
+            .help("Pattern type (one-to-many, many-to-many, many-to-one, chained, self-to-self, zipf, hot-spot, amm, nft-mint, nft-transfer, nft-approve, erc1155-batch, deploy, create2, storage, blob, set-code, mix)")
+            .required(true))
+        .arg(Arg::new("num_transactions")
+            .short('t')
+            .long("num-transactions")
+            .value_name("NUM")
+            .help("Number of transactions to generate")
+            .default_value("20")
+            .required(false))
+        .arg(Arg::new("num_groups")
+            .short('g')
+            .long("num-groups")
+            .value_name("NUM")
+            .help("Number of groups to generate")
+            .default_value("4")
+            .required(false))
+        .arg(Arg::new("conflict_rate")
+            .short('c')
+            .long("conflict-rate")
+            .value_name("RATE")
+            .help("Conflict rate (0.0 to 1.0)")
+            .default_value("0.5")
+            .required(false))
+        .arg(Arg::new("erc20")
+            .long("erc20")
+            .help("Whether to generate ERC20 transactions")
+            .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("accounts")
+            .long("accounts")
+            .value_name("NUM")
+            .help("Size of the account pool of the zipf, hot-spot, amm, nft, erc1155, deployment, storage and blob patterns, or of delegated accounts of the set-code pattern (default: one per transaction, or per authorization)")
+            .required(false))
+        .arg(Arg::new("zipf_exponent")
+            .long("zipf-exponent")
+            .value_name("S")
+            .help("Skew of the zipf and amm patterns: account (or pair) of rank k is picked with probability ~ 1/k^S")
+            .default_value("1.0")
+            .required(false))
+        .arg(Arg::new("hot_fraction")
+            .long("hot-fraction")
+            .value_name("FRACTION")
+            .help("Fraction of the account pool that is hot in the hot-spot pattern")
+            .default_value("0.01")
+            .required(false))
+        .arg(Arg::new("hot_traffic")
+            .long("hot-traffic")
+            .value_name("RATE")
+            .help("Probability that an account of a hot-spot transaction is hot (0.0 to 1.0)")
+            .default_value("0.8")
+            .required(false))
+        .arg(Arg::new("pairs")
+            .long("pairs")
+            .value_name("NUM")
+            .help("Number of AMM pairs of the amm pattern")
+            .default_value("16")
+            .required(false))
+        .arg(Arg::new("batch_size")
+            .long("batch-size")
+            .value_name("NUM")
+            .help("Number of token ids transferred by each transaction of the erc1155-batch pattern")
+            .default_value("10")
+            .required(false))
+        .arg(Arg::new("id_overlap")
+            .long("id-overlap")
+            .value_name("RATE")
+            .help("Fraction of each erc1155-batch batch drawn from token ids shared by all transactions (0.0 to 1.0)")
+            .default_value("0.5")
+            .required(false))
+        .arg(Arg::new("template")
+            .long("template")
+            .value_name("TEMPLATE")
+            .help("Contract deployed by the deploy and create2 patterns (erc20, erc721, erc1155, empty)")
+            .default_value("erc20")
+            .required(false))
+        .arg(Arg::new("code_size")
+            .long("code-size")
+            .value_name("BYTES")
+            .help("Size the deployed code is padded to with random bytes, at most 24576 (default: template size)")
+            .required(false))
+        .arg(Arg::new("slots")
+            .long("slots")
+            .value_name("NUM")
+            .help("Number of storage slots accessed by each transaction of the storage pattern")
+            .default_value("10")
+            .required(false))
+        .arg(Arg::new("write_ratio")
+            .long("write-ratio")
+            .value_name("RATE")
+            .help("Fraction of the slots of each storage transaction that are written (0.0 to 1.0)")
+            .default_value("0.5")
+            .required(false))
+        .arg(Arg::new("key_overlap")
+            .long("key-overlap")
+            .value_name("RATE")
+            .help("Fraction of the slots of each storage transaction shared by all transactions (0.0 to 1.0)")
+            .default_value("0.1")
+            .required(false))
+        .arg(Arg::new("blobs")
+            .long("blobs")
+            .value_name("NUM")
+            .help("Number of blobs of each transaction of the blob pattern, fixed (2) or drawn uniformly from a range (1..6)")
+            .default_value("1")
+            .required(false))
+        .arg(Arg::new("blob_fee")
+            .long("blob-fee")
+            .value_name("WEI")
+            .help("Max fee per blob gas of the blob pattern, in wei: fixed (1) or drawn uniformly from a range (1..100)")
+            .default_value("1")
+            .required(false))
+        .arg(Arg::new("blob_sidecars")
+            .long("blob-sidecars")
+            .help("Whether blob transactions carry their blobs, KZG commitments and proofs (256 KiB of hex per blob)")
+            .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("authorizations")
+            .long("authorizations")
+            .value_name("NUM")
+            .help("Number of authorizations of each transaction of the set-code pattern")
+            .default_value("1")
+            .required(false))
+        .arg(Arg::new("call_ratio")
+            .long("call-ratio")
+            .value_name("RATIO")
+            .help("Share of the transactions of the set-code pattern calling into delegated accounts")
+            .default_value("0.5")
+            .required(false))
+        .arg(Arg::new("mix")
+            .long("mix")
+            .value_name("COMPONENTS")
+            .help("Comma-separated components of the mix pattern as <pattern>[:erc20][=<weight>], e.g. m2m=5,o2m:erc20=3,amm=1,deploy=1")
+            .required_if_eq("type", "mix"))
+        .arg(Arg::new("interleave")
+            .long("interleave")
+            .value_name("STRATEGY")
+            .help("Order of the transactions of the mix pattern (sequential, shuffle, round-robin)")
+            .default_value("shuffle")
+            .required(false))
+        .arg(Arg::new("tx_type")
+            .long("tx-type")
+            .value_name("TYPE")
+            .help("Type of the transactions (legacy, access-list, dynamic-fee; or 0, 1, 2)")
+            .default_value("legacy")
+            .required(false))
+        .arg(Arg::new("gas_price")
+            .long("gas-price")
+            .value_name("WEI")
+            .help("Gas price, or max fee per gas of dynamic-fee transactions, in wei: fixed (10) or drawn uniformly from a range (10..100)")
+            .default_value("10")
+            .required(false))
+        .arg(Arg::new("priority_fee")
+            .long("priority-fee")
+            .value_name("WEI")
+            .help("Max priority fee per gas of dynamic-fee transactions, in wei: fixed (1) or drawn uniformly from a range (1..20), capped to the max fee")
+            .default_value("0")
+            .required(false))
+        .arg(Arg::new("access_list")
+            .long("access-list")
+            .value_name("MODE")
+            .help("Access lists of access-list and dynamic-fee transactions: empty, precise (every storage key accessed), partial[=<coverage>] (each key kept with probability coverage, 0.5 by default) or wrong (random keys)")
+            .default_value("empty")
+            .required(false))
+        .arg(Arg::new("chain_id")
+            .long("chain-id")
+            .value_name("ID")
+            .help("Chain id the transactions of --raw and the EIP-7702 authorizations are signed for")
+            .default_value("1")
+            .required(false))
+        .arg(Arg::new("raw")
+            .long("raw")
+            .help("Write the transactions signed and RLP-encoded, with their hashes and the pre state, instead of a test case")
+            .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("seed")
+            .long("seed")
+            .value_name("SEED")
+            .help("Seed for reproducible output (random if omitted, printed on every run)")
+            .required(false))
+}
+
+/// Generates the `pre` state and the transactions of a `pattern` subcommand
+fn generate_pattern(sub_m: &ArgMatches) -> Result<PatternData, Box<dyn std::error::Error>> {
+    let pattern_type = sub_m.get_one::<String>("type").unwrap();
+    let num_transactions = sub_m
+        .get_one::<String>("num_transactions")
+        .unwrap()
+        .parse()?;
+    let num_groups = sub_m.get_one::<String>("num_groups").unwrap().parse()?;
+    let conflict_rate = sub_m.get_one::<String>("conflict_rate").unwrap().parse()?;
+    let is_erc20 = *sub_m.get_one::<bool>("erc20").unwrap_or(&false);
+    let seed = match sub_m.get_one::<String>("seed") {
+        Some(seed) => seed.parse()?,
+        None => rand::random(),
+    };
+    println!("Using seed {}", seed);
+
+    let accounts = match sub_m.get_one::<String>("accounts") {
+        Some(accounts) => Some(accounts.parse()?),
+        None => None,
+    };
+    let skew = SkewConfig {
+        accounts,
+        zipf_exponent: sub_m.get_one::<String>("zipf_exponent").unwrap().parse()?,
+        hot_fraction: sub_m.get_one::<String>("hot_fraction").unwrap().parse()?,
+        hot_traffic: sub_m.get_one::<String>("hot_traffic").unwrap().parse()?,
+        pairs: sub_m.get_one::<String>("pairs").unwrap().parse()?,
+    };
+
+    let mut tx_gen = TransactionGenerator::with_seed(seed);
+    tx_gen.set_chain_id(sub_m.get_one::<String>("chain_id").unwrap().parse()?);
+    tx_gen.set_skew(skew);
+    tx_gen.set_batch(BatchConfig {
+        batch_size: sub_m.get_one::<String>("batch_size").unwrap().parse()?,
+        id_overlap: sub_m.get_one::<String>("id_overlap").unwrap().parse()?,
+    });
+    let code_size = match sub_m.get_one::<String>("code_size") {
+        Some(code_size) => Some(code_size.parse()?),
+        None => None,
+    };
+    tx_gen.set_deploy(DeployConfig {
+        template: sub_m.get_one::<String>("template").unwrap().parse()?,
+        code_size,
+    });
+    tx_gen.set_fees(FeeConfig {
+        tx_type: sub_m.get_one::<String>("tx_type").unwrap().parse()?,
+        gas_price: sub_m.get_one::<String>("gas_price").unwrap().parse()?,
+        priority_fee: sub_m.get_one::<String>("priority_fee").unwrap().parse()?,
+    });
+    let blobs = sub_m.get_one::<String>("blobs").unwrap();
+    let blobs_per_tx = match blobs.split_once("..") {
+        Some((min, max)) => min.parse()?..=max.parse()?,
+        None => blobs.parse()?..=blobs.parse()?,
+    };
+    tx_gen.set_blobs(BlobConfig {
+        blobs_per_tx,
+        max_fee_per_blob_gas: sub_m.get_one::<String>("blob_fee").unwrap().parse()?,
+        sidecars: *sub_m.get_one::<bool>("blob_sidecars").unwrap_or(&false),
+    });
+    tx_gen.set_set_code(SetCodeConfig {
+        authorizations_per_tx: sub_m.get_one::<String>("authorizations").unwrap().parse()?,
+        call_ratio: sub_m.get_one::<String>("call_ratio").unwrap().parse()?,
+    });
+    tx_gen.set_access_lists(sub_m.get_one::<String>("access_list").unwrap().parse()?);
+    tx_gen.set_storage(StorageConfig {
+        slots_per_tx: sub_m.get_one::<String>("slots").unwrap().parse()?,
+        write_ratio: sub_m.get_one::<String>("write_ratio").unwrap().parse()?,
+        key_overlap: sub_m.get_one::<String>("key_overlap").unwrap().parse()?,
+    });
+    if pattern_type == "mix" {
+        let components = sub_m
+            .get_one::<String>("mix")
+            .unwrap()
+            .split(',')
+            .map(str::parse)
+            .collect::<Result<Vec<MixComponent>, _>>()?;
+        let interleaving: Interleaving = sub_m.get_one::<String>("interleave").unwrap().parse()?;
+        tx_gen.generate_mixed_transactions(
+            &components,
+            num_transactions,
+            num_groups,
+            conflict_rate,
+            interleaving,
+        )?;
+    } else {
+        tx_gen.generate_pattern_transactions(
+            pattern_type,
+            num_transactions,
+            num_groups,
+            conflict_rate,
+            is_erc20,
+        )?;
+    }
+    Ok(tx_gen.get_data())
+}
+
+/// Keys of a scenario case that are not options of the `pattern` subcommand
+const SCENARIO_CASE_KEYS: [&str; 4] = ["name", "output", "env", "fork"];
+
+fn scenario_command() -> Command {
+    Command::new("scenario")
+        .about("Generate all the test cases described in a scenario file")
+        .long_about("Generates all the test cases described in a TOML or YAML scenario file, in one invocation.\n\n\
+                     The file holds a list of `cases` and optional `defaults` shared by all of them. The keys of\n\
+                     a case are the long options of the `pattern` subcommand (`type`, `num-transactions`,\n\
+                     `conflict-rate`, `erc20`, `seed`, ...), plus:\n\n\
+                     - name: Name of the test case, required\n\n\
+                     - output: Path of the test case, where `{name}` is replaced by the case name\n\
+                     \t    (default: ./data/{name}.json)\n\n\
+                     - env: Fields of the `env` of the test case overriding the default ones\n\n\
+                     - fork: Fork of the `post` section of the test case (default: Cancun, or Prague for\n\
+                     \t    set-code transactions)\n\n\
+                     An option given a list of values expands the case into one test case per combination of\n\
+                     values, each named after the values it was given. The `-o` option is not used.")
+        .arg(Arg::new("file")
+            .value_name("FILE")
+            .help("Scenario file (.toml, .yaml or .yml)")
+            .required(true))
+}
+
+/// Reads a scenario file, as TOML or YAML depending on its extension
+fn load_scenario(path: &str) -> Result<Value, Box<dyn std::error::Error>> {
+    let content = std::fs::read_to_string(path)?;
+    match Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+    {
+        Some("toml") => Ok(toml::from_str(&content)?),
+        Some("yaml" | "yml") => Ok(serde_yaml::from_str(&content)?),
+        _ => Err(format!(
+            "unknown format of scenario file {}, expected .toml, .yaml or .yml",
+            path
+        )
+        .into()),
+    }
+}
+
+fn scenario_value(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Expands the options of a case given a list of values into one case per combination, each
+/// named after its values
+fn expand_case(case: &Map<String, Value>, name: &str) -> Vec<(String, Map<String, Value>)> {
+    let mut cases = vec![(name.to_string(), case.clone())];
+    for (key, value) in case {
+        let Value::Array(values) = value else {
+            continue;
+        };
+        if key == "mix" {
+            continue;
+        }
+        cases = cases
+            .into_iter()
+            .flat_map(|(name, case)| {
+                values.iter().map(move |value| {
+                    let mut case = case.clone();
+                    case.insert(key.clone(), value.clone());
+                    (format!("{}-{}-{}", name, key, scenario_value(value)), case)
+                })
+            })
+            .collect();
+    }
+    cases
+}
+
+/// Turns the options of a case into the arguments of the `pattern` subcommand
+fn case_args(case: &Map<String, Value>) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut args = vec![];
+    for (key, value) in case {
+        if SCENARIO_CASE_KEYS.contains(&key.as_str()) {
+            continue;
+        }
+        let value = match value {
+            Value::Bool(true) => {
+                args.push(format!("--{}", key));
+                continue;
+            }
+            Value::Bool(false) => continue,
+            Value::String(_) | Value::Number(_) => scenario_value(value),
+            Value::Array(components) if key == "mix" => components
+                .iter()
+                .map(scenario_value)
+                .collect::<Vec<_>>()
+                .join(","),
+            _ => return Err(format!("invalid value of '{}': {}", key, value).into()),
+        };
+        args.push(format!("--{}", key));
+        args.push(value);
+    }
+    Ok(args)
+}
+
+/// Generates and writes all the test cases of a scenario file
+fn generate_scenario(path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let scenario = load_scenario(path)?;
+    let scenario = scenario
+        .as_object()
+        .ok_or("a scenario must be a table of `defaults` and `cases`")?;
+    if let Some(key) = scenario
+        .keys()
+        .find(|key| !["defaults", "cases"].contains(&key.as_str()))
+    {
+        return Err(format!("unknown scenario key '{}'", key).into());
+    }
+    let defaults = match scenario.get("defaults") {
+        Some(defaults) => defaults
+            .as_object()
+            .ok_or("scenario `defaults` must be a table")?
+            .clone(),
+        None => Map::new(),
+    };
+    let cases = scenario
+        .get("cases")
+        .and_then(Value::as_array)
+        .filter(|cases| !cases.is_empty())
+        .ok_or("a scenario needs a non-empty list of `cases`")?;
+
+    for (idx, case) in cases.iter().enumerate() {
+        let case = case
+            .as_object()
+            .ok_or_else(|| format!("case {} of the scenario must be a table", idx))?;
+        let mut options = defaults.clone();
+        options.extend(case.clone());
+        // the env is merged field by field, a case overriding only some of the defaults
+        let mut env = gen_env();
+        for overrides in [defaults.get("env"), case.get("env")].into_iter().flatten() {
+            let overrides = overrides
+                .as_object()
+                .ok_or_else(|| format!("`env` of case {} must be a table", idx))?;
+            env.as_object_mut().unwrap().extend(overrides.clone());
+        }
+        let name = case
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| format!("case {} of the scenario has no name", idx))?;
+        let fork = options.get("fork").map(scenario_value);
+        let output = options
+            .get("output")
+            .map_or("./data/{name}.json".to_string(), scenario_value);
+
+        let expanded = expand_case(&options, name);
+        if expanded.len() > 1 && !output.contains("{name}") {
+            return Err(format!(
+                "case {} has several values of an option, its output must contain {{name}}",
+                name
+            )
+            .into());
+        }
+        for (name, options) in expanded {
+            println!("Generating {}", name);
+            let sub_m = pattern_command()
+                .no_binary_name(true)
+                .try_get_matches_from(case_args(&options)?)
+                .map_err(|err| format!("case {}: {}", name, err))?;
+            let json_output = build_pattern_output(
+                &sub_m,
+                generate_pattern(&sub_m)?,
+                env.clone(),
+                name.clone(),
+                fork.as_deref(),
+            )?;
+            let file_path = output.replace("{name}", &name);
+            if let Some(dir) = Path::new(&file_path).parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            write_test_case(&file_path, &json_output)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_test_case(file_path: &str, json_output: &Value) -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = File::create(file_path)?;
+    file.write_all(serde_json::to_string_pretty(json_output)?.as_bytes())?;
+    println!("Test cases written to {}", file_path);
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let matches = Command::new("Ethereum Transaction Generator")
@@ -68,102 +636,53 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             .help("Output JSON file path")
             .global(true)
             .default_value("./data/my_test_case.json"))
-        .subcommand(Command::new("pattern")
-            .about("Generate transactions based on a pattern")
-            .long_about("Generates transactions following a specific pattern. Available patterns are:\n\n\
-                         - one-to-many: One sender, multiple receivers\n\
-                         \t            o                              \n\
-                         \t    ┌---┬---┼---┬---┐                      \n\
-                         \t    ↓   ↓   ↓   ↓   ↓                      \n\
-                         \t    o   o   o   o   o                      \n\n\
-                         - many-to-many: Multiple senders, multiple receivers\n\
-                         \t    o   o   o   o   o                      \n\
-                         \t    ↓   ↓   ↓   ↓   ↓                      \n\
-                         \t    o   o   o   o   o                      \n\n\
-                         - many-to-one: Multiple senders, one receiver\n\
-                         \t    o   o   o   o   o                      \n\
-                         \t    ↓   ↓   ↓   ↓   ↓                      \n\
-                         \t    └---┴---┼---┴---┘                      \n\
-                         \t            o                              \n\n\
-                         - chained: Transactions form a circular pattern\n\
-                         \t    o → o → o → o → o                      \n\
-                         \t    ↑               ↓                      \n\
-                         \t    o ← o ← o ← o ← o                      \n\n\
-                         - self-to-self: Accounts transfer to themselves\n\
-                         \t    o   o   o   o   o                      \n\
-                         \t    ↓   ↓   ↓   ↓   ↓                      \n\
-                         \t    o   o   o   o   o                      \n\
-                         ")
-            .arg(Arg::new("type")
-                .short('y')
-                .long("type")
-                .value_name("TYPE")
-                .help("Pattern type (one-to-many, many-to-many, many-to-one, chained, self-to-self)")
-                .required(true))
-            .arg(Arg::new("num_transactions")
-                .short('t')
-                .long("num-transactions")
-                .value_name("NUM")
-                .help("Number of transactions to generate")
-                .default_value("20")
-                .required(false))
-            .arg(Arg::new("num_groups")
-                .short('g')
-                .long("num-groups")
-                .value_name("NUM")
-                .help("Number of groups to generate")
-                .default_value("4")
-                .required(false))
-            .arg(Arg::new("conflict_rate")
-                .short('c')
-                .long("conflict-rate")
-                .value_name("RATE")
-                .help("Conflict rate (0.0 to 1.0)")
-                .default_value("0.5")
-                .required(false))
-            .arg(Arg::new("erc20")
-                .long("erc20")
-                .help("Whether to generate ERC20 transactions")
-                .action(clap::ArgAction::SetTrue)))
+        .subcommand(pattern_command())
+        .subcommand(scenario_command())
         .after_help("Examples:\n\
                      Generate 50 transactions in a chained pattern:\n\
                      $ ./target/release/generate pattern -y chained -t 50\n\n\
                      Specify output file:\n\
-                     $ ./target/release/generate pattern -y chained -t 50 -o ./my_test_case.json")
+                     $ ./target/release/generate pattern -y chained -t 50 -o ./my_test_case.json\n\n\
+                     80% of the transactions touching 1% of 10000 accounts:\n\
+                     $ ./target/release/generate pattern -y hot-spot -t 5000 --accounts 10000 --hot-fraction 0.01 --hot-traffic 0.8\n\n\
+                     Swaps on 32 pools, most of them on the few most popular ones:\n\
+                     $ ./target/release/generate pattern -y amm -t 1000 --pairs 32 --zipf-exponent 1.2\n\n\
+                     Deploy 100 ERC-721 contracts padded to the maximum code size through a CREATE2 factory:\n\
+                     $ ./target/release/generate pattern -y create2 -t 100 --template erc721 --code-size 24576\n\n\
+                     A block of ETH and ERC20 transfers, swaps and deployments in random order:\n\
+                     $ ./target/release/generate pattern -y mix -t 1000 --mix m2m=5,o2m:erc20=3,amm=1,deploy=1\n\n\
+                     EIP-1559 transactions with fees drawn from ranges, for a base fee of 10 wei:\n\
+                     $ ./target/release/generate pattern -y m2m -t 1000 --tx-type dynamic-fee --gas-price 20..200 --priority-fee 1..50\n\n\
+                     ERC20 transfers with precise EIP-2930 access lists:\n\
+                     $ ./target/release/generate pattern -y m2m -t 1000 --erc20 --tx-type access-list --access-list precise\n\n\
+                     Blob transactions with 1 to 6 blobs each (built with the `blob` feature):\n\
+                     $ ./target/release/generate pattern -y blob -t 100 --blobs 1..6 --blob-fee 1..10\n\n\
+                     Accounts delegated several times by EIP-7702 authorizations, and called into:\n\
+                     $ ./target/release/generate pattern -y set-code -t 1000 --authorizations 4 --accounts 100\n\n\
+                     Signed raw transactions for chain id 1337, ready for eth_sendRawTransaction:\n\
+                     $ ./target/release/generate pattern -y m2m -t 1000 --tx-type dynamic-fee --raw --chain-id 1337\n\n\
+                     Reproduce a previous run:\n\
+                     $ ./target/release/generate pattern -y m2m -t 100 -c 0.6 --seed 42\n\n\
+                     Generate all the test cases of a benchmark matrix:\n\
+                     $ ./target/release/generate scenario ./benchmarks.toml")
         .get_matches();
 
-    let (pre, transactions) = match matches.subcommand() {
+    match matches.subcommand() {
         Some(("pattern", sub_m)) => {
-            let pattern_type = sub_m.get_one::<String>("type").unwrap();
-            let num_transactions = sub_m
-                .get_one::<String>("num_transactions")
-                .unwrap()
-                .parse()?;
-            let num_groups = sub_m.get_one::<String>("num_groups").unwrap().parse()?;
-            let conflict_rate = sub_m.get_one::<String>("conflict_rate").unwrap().parse()?;
-            let is_erc20 = *sub_m.get_one::<bool>("erc20").unwrap_or(&false);
-
-            let mut tx_gen = TransactionGenerator::new();
-            tx_gen.generate_pattern_transactions(
-                pattern_type,
-                num_transactions,
-                num_groups,
-                conflict_rate,
-                is_erc20,
+            let json_output = build_pattern_output(
+                sub_m,
+                generate_pattern(sub_m)?,
+                gen_env(),
+                "just-test".into(),
+                None,
             )?;
-            tx_gen.get_data()
+            write_test_case(matches.get_one::<String>("output").unwrap(), &json_output)?;
+        }
+        Some(("scenario", sub_m)) => {
+            generate_scenario(sub_m.get_one::<String>("file").unwrap())?;
         }
         _ => return Err("Invalid subcommand".into()),
-    };
-
-    let json_output =
-        build_json_output(pre, transactions, get_info(), gen_env(), "just-test".into())?;
-
-    let file_path = matches.get_one::<String>("output").unwrap();
-    let mut file = File::create(file_path)?;
-    file.write_all(serde_json::to_string_pretty(&json_output)?.as_bytes())?;
-
-    println!("Test cases written to {}", file_path);
+    }
 
     Ok(())
 }
@@ -1 +1,66 @@
-pub const ERC20_USDC_DEPLOYED_BYTECODE: &str = "0x608060405234801561000f575f80fd5b506004361061009c575f3560e01c8063313ce56711610064578063313ce5671461014657806370a082311461016457806395d89b4114610194578063a9059cbb146101b2578063dd62ed3e146101e25761009c565b806306fdde03146100a0578063095ea7b3146100be5780631249c58b146100ee57806318160ddd146100f857806323b872dd14610116575b5f80fd5b6100a8610212565b6040516100b59190610b0b565b60405180910390f35b6100d860048036038101906100d39190610bbc565b6102a2565b6040516100e59190610c14565b60405180910390f35b6100f66102c4565b005b6101006102e1565b60405161010d9190610c3c565b60405180910390f35b610130600480360381019061012b9190610c55565b6102ea565b60405161013d9190610c14565b60405180910390f35b61014e610318565b60405161015b9190610cc0565b60405180910390f35b61017e60048036038101906101799190610cd9565b610320565b60405161018b9190610c3c565b60405180910390f35b61019c610365565b6040516101a99190610b0b565b60405180910390f35b6101cc60048036038101906101c79190610bbc565b6103f5565b6040516101d99190610c14565b60405180910390f35b6101fc60048036038101906101f79190610d04565b610417565b6040516102099190610c3c565b60405180910390f35b60606003805461022190610d6f565b80601f016020809104026020016040519081016040528092919081815260200182805461024d90610d6f565b80156102985780601f1061026f57610100808354040283529160200191610298565b820191905f5260205f20905b81548152906001019060200180831161027b57829003601f168201915b5050505050905090565b5f806102ac610499565b90506102b98185856104a0565b600191505092915050565b6102df6102cf610499565b69d3c21bcecceda10000006104b2565b565b5f600254905090565b5f806102f4610499565b9050610301858285610531565b61030c8585856105c3565b60019150509392505050565b5f6012905090565b5f805f8373ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff1681526020019081526020015f20549050919050565b60606004805461037490610d6f565b80601f01602080910402602001604051908101604052809291908181526020018280546103a090610d6f565b80156103eb5780601f106103c2576101008083540402835291602001916103eb565b820191905f5260205f20905b8154815290600101906020018083116103ce57829003601f168201915b5050505050905090565b5f806103ff610499565b905061040c8185856105c3565b600191505092915050565b5f60015f8473ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff1681526020019081526020015f205f8373ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff1681526020019081526020015f2054905092915050565b5f33905090565b6104ad83838360016106b3565b505050565b5f73ffffffffffffffffffffffffffffffffffffffff168273ffffffffffffffffffffffffffffffffffffffff1603610522575f6040517fec442f050000000000000000000000000000000000000000000000000000000081526004016105199190610dae565b60405180910390fd5b61052d5f8383610882565b5050565b5f61053c8484610417565b90507fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff81146105bd57818110156105ae578281836040517ffb8f41b20000000000000000000000000000000000000000000000000000000081526004016105a593929190610dc7565b60405180910390fd5b6105bc84848484035f6106b3565b5b50505050565b5f73ffffffffffffffffffffffffffffffffffffffff168373ffffffffffffffffffffffffffffffffffffffff1603610633575f6040517f96c6fd1e00000000000000000000000000000000000000000000000000000000815260040161062a9190610dae565b60405180910390fd5b5f73ffffffffffffffffffffffffffffffffffffffff168273ffffffffffffffffffffffffffffffffffffffff16036106a3575f6040517fec442f0500000000000000000000000000000000000000000000000000000000815260040161069a9190610dae565b60405180910390fd5b6106ae838383610882565b505050565b5f73ffffffffffffffffffffffffffffffffffffffff168473ffffffffffffffffffffffffffffffffffffffff1603610723575f6040517fe602df0500000000000000000000000000000000000000000000000000000000815260040161071a9190610dae565b60405180910390fd5b5f73ffffffffffffffffffffffffffffffffffffffff168373ffffffffffffffffffffffffffffffffffffffff1603610793575f6040517f94280d6200000000000000000000000000000000000000000000000000000000815260040161078a9190610dae565b60405180910390fd5b8160015f8673ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff1681526020019081526020015f205f8573ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff1681526020019081526020015f2081905550801561087c578273ffffffffffffffffffffffffffffffffffffffff168473ffffffffffffffffffffffffffffffffffffffff167f8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925846040516108739190610c3c565b60405180910390a35b50505050565b5f73ffffffffffffffffffffffffffffffffffffffff168373ffffffffffffffffffffffffffffffffffffffff16036108d2578060025f8282546108c69190610e29565b925050819055506109a0565b5f805f8573ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff1681526020019081526020015f205490508181101561095b578381836040517fe450d38c00000000000000000000000000000000000000000000000000000000815260040161095293929190610dc7565b60405180910390fd5b8181035f808673ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff1681526020019081526020015f2081905550505b5f73ffffffffffffffffffffffffffffffffffffffff168273ffffffffffffffffffffffffffffffffffffffff16036109e7578060025f8282540392505081905550610a31565b805f808473ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff1681526020019081526020015f205f82825401925050819055505b8173ffffffffffffffffffffffffffffffffffffffff168373ffffffffffffffffffffffffffffffffffffffff167fddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef83604051610a8e9190610c3c565b60405180910390a3505050565b5f81519050919050565b5f82825260208201905092915050565b8281835e5f83830152505050565b5f601f19601f8301169050919050565b5f610add82610a9b565b610ae78185610aa5565b9350610af7818560208601610ab5565b610b0081610ac3565b840191505092915050565b5f6020820190508181035f830152610b238184610ad3565b905092915050565b5f80fd5b5f73ffffffffffffffffffffffffffffffffffffffff82169050919050565b5f610b5882610b2f565b9050919050565b610b6881610b4e565b8114610b72575f80fd5b50565b5f81359050610b8381610b5f565b92915050565b5f819050919050565b610b9b81610b89565b8114610ba5575f80fd5b50565b5f81359050610bb681610b92565b92915050565b5f8060408385031215610bd257610bd1610b2b565b5b5f610bdf85828601610b75565b9250506020610bf085828601610ba8565b9150509250929050565b5f8115159050919050565b610c0e81610bfa565b82525050565b5f602082019050610c275f830184610c05565b92915050565b610c3681610b89565b82525050565b5f602082019050610c4f5f830184610c2d565b92915050565b5f805f60608486031215610c6c57610c6b610b2b565b5b5f610c7986828701610b75565b9350506020610c8a86828701610b75565b9250506040610c9b86828701610ba8565b9150509250925092565b5f60ff82169050919050565b610cba81610ca5565b82525050565b5f602082019050610cd35f830184610cb1565b92915050565b5f60208284031215610cee57610ced610b2b565b5b5f610cfb84828501610b75565b91505092915050565b5f8060408385031215610d1a57610d19610b2b565b5b5f610d2785828601610b75565b9250506020610d3885828601610b75565b9150509250929050565b7f4e487b71000000000000000000000000000000000000000000000000000000005f52602260045260245ffd5b5f6002820490506001821680610d8657607f821691505b602082108103610d9957610d98610d42565b5b50919050565b610da881610b4e565b82525050565b5f602082019050610dc15f830184610d9f565b92915050565b5f606082019050610dda5f830186610d9f565b610de76020830185610c2d565b610df46040830184610c2d565b949350505050565b7f4e487b71000000000000000000000000000000000000000000000000000000005f52601160045260245ffd5b5f610e3382610b89565b9150610e3e83610b89565b9250828201905080821115610e5657610e55610dfc565b5b9291505056fea2646970667358221220ad017518072cb4ae99187c562a54a73900f974e515c9d016bcbfe2dd6879211664736f6c634300081a0033";
\ No newline at end of file
+pub const ERC20_USDC_DEPLOYED_BYTECODE: &str = "0x608060405234801561000f575f80fd5b506004361061009c575f3560e01c8063313ce56711610064578063313ce5671461014657806370a082311461016457806395d89b4114610194578063a9059cbb146101b2578063dd62ed3e146101e25761009c565b806306fdde03146100a0578063095ea7b3146100be5780631249c58b146100ee57806318160ddd146100f857806323b872dd14610116575b5f80fd5b6100a8610212565b6040516100b59190610b0b565b60405180910390f35b6100d860048036038101906100d39190610bbc565b6102a2565b6040516100e59190610c14565b60405180910390f35b6100f66102c4565b005b6101006102e1565b60405161010d9190610c3c565b60405180910390f35b610130600480360381019061012b9190610c55565b6102ea565b60405161013d9190610c14565b60405180910390f35b61014e610318565b60405161015b9190610cc0565b60405180910390f35b61017e60048036038101906101799190610cd9565b610320565b60405161018b9190610c3c565b60405180910390f35b61019c610365565b6040516101a99190610b0b565b60405180910390f35b6101cc60048036038101906101c79190610bbc565b6103f5565b6040516101d99190610c14565b60405180910390f35b6101fc60048036038101906101f79190610d04565b610417565b6040516102099190610c3c565b60405180910390f35b60606003805461022190610d6f565b80601f016020809104026020016040519081016040528092919081815260200182805461024d90610d6f565b80156102985780601f1061026f57610100808354040283529160200191610298565b820191905f5260205f20905b81548152906001019060200180831161027b57829003601f168201915b5050505050905090565b5f806102ac610499565b90506102b98185856104a0565b600191505092915050565b6102df6102cf610499565b69d3c21bcecceda10000006104b2565b565b5f600254905090565b5f806102f4610499565b9050610301858285610531565b61030c8585856105c3565b60019150509392505050565b5f6012905090565b5f805f8373ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff1681526020019081526020015f20549050919050565b60606004805461037490610d6f565b80601f01602080910402602001604051908101604052809291908181526020018280546103a090610d6f565b80156103eb5780601f106103c2576101008083540402835291602001916103eb565b820191905f5260205f20905b8154815290600101906020018083116103ce57829003601f168201915b5050505050905090565b5f806103ff610499565b905061040c8185856105c3565b600191505092915050565b5f60015f8473ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff1681526020019081526020015f205f8373ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff1681526020019081526020015f2054905092915050565b5f33905090565b6104ad83838360016106b3565b505050565b5f73ffffffffffffffffffffffffffffffffffffffff168273ffffffffffffffffffffffffffffffffffffffff1603610522575f6040517fec442f050000000000000000000000000000000000000000000000000000000081526004016105199190610dae565b60405180910390fd5b61052d5f8383610882565b5050565b5f61053c8484610417565b90507fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff81146105bd57818110156105ae578281836040517ffb8f41b20000000000000000000000000000000000000000000000000000000081526004016105a593929190610dc7565b60405180910390fd5b6105bc84848484035f6106b3565b5b50505050565b5f73ffffffffffffffffffffffffffffffffffffffff168373ffffffffffffffffffffffffffffffffffffffff1603610633575f6040517f96c6fd1e00000000000000000000000000000000000000000000000000000000815260040161062a9190610dae565b60405180910390fd5b5f73ffffffffffffffffffffffffffffffffffffffff168273ffffffffffffffffffffffffffffffffffffffff16036106a3575f6040517fec442f0500000000000000000000000000000000000000000000000000000000815260040161069a9190610dae565b60405180910390fd5b6106ae838383610882565b505050565b5f73ffffffffffffffffffffffffffffffffffffffff168473ffffffffffffffffffffffffffffffffffffffff1603610723575f6040517fe602df0500000000000000000000000000000000000000000000000000000000815260040161071a9190610dae565b60405180910390fd5b5f73ffffffffffffffffffffffffffffffffffffffff168373ffffffffffffffffffffffffffffffffffffffff1603610793575f6040517f94280d6200000000000000000000000000000000000000000000000000000000815260040161078a9190610dae565b60405180910390fd5b8160015f8673ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff1681526020019081526020015f205f8573ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff1681526020019081526020015f2081905550801561087c578273ffffffffffffffffffffffffffffffffffffffff168473ffffffffffffffffffffffffffffffffffffffff167f8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925846040516108739190610c3c565b60405180910390a35b50505050565b5f73ffffffffffffffffffffffffffffffffffffffff168373ffffffffffffffffffffffffffffffffffffffff16036108d2578060025f8282546108c69190610e29565b925050819055506109a0565b5f805f8573ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff1681526020019081526020015f205490508181101561095b578381836040517fe450d38c00000000000000000000000000000000000000000000000000000000815260040161095293929190610dc7565b60405180910390fd5b8181035f808673ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff1681526020019081526020015f2081905550505b5f73ffffffffffffffffffffffffffffffffffffffff168273ffffffffffffffffffffffffffffffffffffffff16036109e7578060025f8282540392505081905550610a31565b805f808473ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff1681526020019081526020015f205f82825401925050819055505b8173ffffffffffffffffffffffffffffffffffffffff168373ffffffffffffffffffffffffffffffffffffffff167fddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef83604051610a8e9190610c3c565b60405180910390a3505050565b5f81519050919050565b5f82825260208201905092915050565b8281835e5f83830152505050565b5f601f19601f8301169050919050565b5f610add82610a9b565b610ae78185610aa5565b9350610af7818560208601610ab5565b610b0081610ac3565b840191505092915050565b5f6020820190508181035f830152610b238184610ad3565b905092915050565b5f80fd5b5f73ffffffffffffffffffffffffffffffffffffffff82169050919050565b5f610b5882610b2f565b9050919050565b610b6881610b4e565b8114610b72575f80fd5b50565b5f81359050610b8381610b5f565b92915050565b5f819050919050565b610b9b81610b89565b8114610ba5575f80fd5b50565b5f81359050610bb681610b92565b92915050565b5f8060408385031215610bd257610bd1610b2b565b5b5f610bdf85828601610b75565b9250506020610bf085828601610ba8565b9150509250929050565b5f8115159050919050565b610c0e81610bfa565b82525050565b5f602082019050610c275f830184610c05565b92915050565b610c3681610b89565b82525050565b5f602082019050610c4f5f830184610c2d565b92915050565b5f805f60608486031215610c6c57610c6b610b2b565b5b5f610c7986828701610b75565b9350506020610c8a86828701610b75565b9250506040610c9b86828701610ba8565b9150509250925092565b5f60ff82169050919050565b610cba81610ca5565b82525050565b5f602082019050610cd35f830184610cb1565b92915050565b5f60208284031215610cee57610ced610b2b565b5b5f610cfb84828501610b75565b91505092915050565b5f8060408385031215610d1a57610d19610b2b565b5b5f610d2785828601610b75565b9250506020610d3885828601610b75565b9150509250929050565b7f4e487b71000000000000000000000000000000000000000000000000000000005f52602260045260245ffd5b5f6002820490506001821680610d8657607f821691505b602082108103610d9957610d98610d42565b5b50919050565b610da881610b4e565b82525050565b5f602082019050610dc15f830184610d9f565b92915050565b5f606082019050610dda5f830186610d9f565b610de76020830185610c2d565b610df46040830184610c2d565b949350505050565b7f4e487b71000000000000000000000000000000000000000000000000000000005f52601160045260245ffd5b5f610e3382610b89565b9150610e3e83610b89565b9250828201905080821115610e5657610e55610dfc565b5b9291505056fea2646970667358221220ad017518072cb4ae99187c562a54a73900f974e515c9d016bcbfe2dd6879211664736f6c634300081a0033";
+
+/// Runtime bytecode of a minimal Uniswap-V2-style constant-product pair, hand-assembled.
+///
+/// Storage: slot 0 `token0`, slot 1 `token1`, slot 2 `reserve0`, slot 3 `reserve1`. The
+/// tokens are ERC20 contracts with balances at slot 0 and allowances at slot 1, such as
+/// [`ERC20_USDC_DEPLOYED_BYTECODE`].
+///
+/// - `swap(uint256 amountIn, bool zeroForOne)` (`0x2aea6605`): pulls `amountIn` of the input
+///   token from the caller with `transferFrom`, sends back
+///   `amountOut = amountIn * 997 * reserveOut / (reserveIn * 1000 + amountIn * 997)` (the
+///   Uniswap V2 formula with its 0.3% fee) of the other token with `transfer`, updates both
+///   reserves and returns `amountOut`; reverts if `amountOut` is zero or a transfer fails
+/// - `getReserves()` (`0x0902f1ac`), `token0()` (`0x0dfe1681`), `token1()` (`0xd21220a7`)
+pub const AMM_PAIR_DEPLOYED_BYTECODE: &str = "0x60003560e01c80632aea6605146100605780630902f1ac146100365780630dfe1681146100485763d21220a714610054575b600080fd5b60025460005260035460205260406000f35b60005460005260206000f35b60015460005260206000f35b5060243515801560043582600201548260020154826103e50280836103e80201908202048015610031576323b872dd60e01b600052336004523060245283604452602060006064600060008a545af1156100315763a9059cbb60e01b60005233600452806024526020600060446000600089545af115610031578082038560020155828401866002015560005260206000f3";
+
+/// Runtime bytecode of a minimal ERC-721 contract, hand-assembled.
+///
+/// Storage: slot 0 the next token id (also the total supply), slot 1 `owners`
+/// (`mapping(uint256 => address)`), slot 2 `balances` (`mapping(address => uint256)`), slot 3
+/// `tokenApprovals` (`mapping(uint256 => address)`).
+///
+/// - `mint()` (`0x1249c58b`): mints the next token id to the caller and returns it
+/// - `transferFrom(address from, address to, uint256 tokenId)` (`0x23b872dd`): moves a token
+///   owned by `from`, if the caller is `from` or approved for the token, and clears its
+///   approval
+/// - `approve(address to, uint256 tokenId)` (`0x095ea7b3`): approves `to` for a token owned by
+///   the caller
+/// - `ownerOf(uint256)` (`0x6352211e`), `balanceOf(address)` (`0x70a08231`),
+///   `getApproved(uint256)` (`0x081812fc`), `totalSupply()` (`0x18160ddd`)
+///
+/// `Transfer` and `Approval` events are emitted as in ERC-721. Operator approvals and safe
+/// transfers are not supported.
+pub const ERC721_DEPLOYED_BYTECODE: &str = "0x60003560e01c80631249c58b146100b357806323b872dd14610111578063095ea7b3146101c55780636352211e1461006057806370a082311461007d578063081812fc14610094576318160ddd146100ab575b600080fd5b60005260206000f35b506004356000526001602052604060002054801561005257610057565b506004356000526002602052604060002054610057565b506004356000526003602052604060002054610057565b600054610057565b506000548060010160005533816000526001602052604060002055336000526002602052604060002080546001019055803360007fddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef600080a4610057565b506044356024356004358260005260016020526040600020548114156100525780331461014f57826000526003602052604060002054331415610052575b600083600052600360205260406000205581836000526001602052604060002055806000526002602052604060002080546001900390558160005260026020526040600020805460010190558282827fddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef600080a4005b506024356004358160005260016020526040600020548033141561005257818360005260036020526040600020558282827f8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925600080a400";
+
+/// Runtime bytecode of a minimal ERC-1155 contract, hand-assembled.
+///
+/// Storage: slot 0 `balances` (`mapping(uint256 id => mapping(address => uint256))`), as in
+/// OpenZeppelin's `ERC1155`.
+///
+/// - `safeBatchTransferFrom(address from, address to, uint256[] ids, uint256[] values,
+///   bytes data)` (`0x2eb2c2d6`): moves `values[i]` of token `ids[i]` from `from` to `to` for
+///   every `i`, if the caller is `from`, and emits `TransferBatch`; reverts if the arrays
+///   differ in length or a balance is insufficient
+/// - `balanceOf(address, uint256)` (`0x00fdd58e`)
+///
+/// Operator approvals and the `onERC1155BatchReceived` check on contract receivers are not
+/// supported.
+pub const ERC1155_DEPLOYED_BYTECODE: &str = "0x60003560e01c80632eb2c2d614610048576300fdd58e14610020575b600080fd5b6024356000526000602052604060002060205260043560005260406000205460005260206000f35b506004358033141561001b576024356044356004016064356004018135808235141561001b5760005b818110156100c9578060010160051b80850135908401358160005260006020526040600020602052876000526040600020805482811061001b5782900390558660005260406000208054820190555050600101610071565b5060010160051b6040600052806040016020528083604037808282604001378385337f4a39dc06d4c0dbc64b70af90fd698a233a518aa5d07e595d983b8c0526c8f7fb8460011b6040016000a400";
+
+/// Address of the deterministic deployment proxy, the CREATE2 factory deployed at the same
+/// address on most EVM chains.
+pub const CREATE2_FACTORY_ADDRESS: &str = "0x4e59b44847b379578588920ca78fbf26c0b4956c";
+
+/// Runtime bytecode of the deterministic deployment proxy: called with `salt ++ initCode`
+/// as calldata, it deploys `initCode` with CREATE2 and returns the 20-byte address of the new
+/// contract, or reverts if the deployment fails.
+pub const CREATE2_FACTORY_DEPLOYED_BYTECODE: &str = "0x7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffe03601600081602082378035828234f58015156039578182fd5b8082525050506014600cf3";
+
+/// Runtime bytecode of a synthetic contract accessing the storage slots given in calldata,
+/// hand-assembled.
+///
+/// The calldata is a sequence of 32-byte words, one per storage access, without a function
+/// selector. The lower 255 bits of a word are the slot; if the top bit is clear, the slot is
+/// read (`SLOAD`), otherwise it is incremented (`SLOAD` then `SSTORE`).
+pub const STORAGE_ACCESS_DEPLOYED_BYTECODE: &str = "0x60005b803611156100345780358060ff1c9060011b60011c80549161002557505061002c565b9060010190555b602001610002565b00";
@@ -3,4 +3,8 @@ pub mod profiler;
 #[cfg(feature = "generator")]
 pub mod transaction_generator;
 #[cfg(feature = "generator")]
-pub use transaction_generator::TransactionGenerator;
+pub use transaction_generator::{
+    sign_transaction, AccessListMode, BatchConfig, BlobConfig, ContractTemplate, DeployConfig,
+    FeeConfig, FeeDistribution, Interleaving, MixComponent, SetCodeConfig, SkewConfig,
+    StorageConfig, TransactionGenerator, TxType,
+};
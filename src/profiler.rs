@@ -10,7 +10,15 @@
 //! - Task timing with precise start/end markers
 //! - Thread-safe profiling in concurrent environments
 //! - Rich event annotation system
-//! - Multiple output formats (JSON, ZIP)
+//! - Multiple output formats (JSON, ZIP, compact binary, CSV, Parquet, OTLP, HTML report)
+//! - Configurable dumps: compact JSON, filters by task, type and thread, time windows
+//! - Detection of tasks never ended and suspiciously long spans
+//! - Counter tracks and a background resource sampler (Linux)
+//! - Optional per-task thread CPU time measurement
+//! - Optional per-task hardware performance counters (Linux, `perf` feature)
+//! - Statistical summary of task runtimes
+//! - Parallel-execution analytics over transaction and commit events
+//! - Live HTTP endpoint with dumps and Prometheus metrics (`http` feature)
 //! - Special handling for transaction and commit events
 //! - Global singleton instance with thread-safe access
 //! 
@@ -97,37 +105,97 @@ use std::{
     io::{BufWriter, Write},
     sync::Mutex,
     thread::current,
-    time::Instant,
+    time::{Instant, SystemTime, UNIX_EPOCH},
 };
 use zip::{write::FileOptions, CompressionMethod, ZipWriter};
 
+mod analysis;
+pub use analysis::{analysis, analysis_text, analyze};
+mod binary;
+pub use binary::{dump_binary, from_binary, load_binary, to_binary};
+#[cfg(all(feature = "perf", target_os = "linux"))]
+mod perf;
+#[cfg(all(feature = "perf", target_os = "linux"))]
+pub use perf::enable_perf_counters;
+#[cfg(target_os = "linux")]
+mod sampler;
+#[cfg(target_os = "linux")]
+pub use sampler::{start_sampler, Sampler};
+#[cfg(feature = "http")]
+mod server;
+#[cfg(feature = "http")]
+pub use server::{start_server, HttpServer};
+mod html;
+pub use html::{dump_html, to_html};
+mod options;
+pub use options::{dump_json_with, dump_with, dump_zip_with, DumpOptions};
+mod otlp;
+pub use otlp::{dump_otlp, export_otlp, to_otlp};
+mod summary;
+pub use summary::{summarize, summary};
+mod table;
+mod validate;
+pub use validate::{validate, validation, LONG_SPAN_FACTOR, LONG_SPAN_MIN_SAMPLES};
+#[cfg(feature = "parquet")]
+pub use table::{dump_parquet, write_parquet};
+pub use table::{dump_csv, to_csv};
+
 /// Global profiler instance initialized lazily
 static PROFILER: Lazy<Mutex<Profiler>> = Lazy::new(|| {
     Mutex::new(Profiler {
         genesis: Instant::now(),
+        genesis_unix_nano: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos(),
         thread_tasks: HashMap::new(),
         global_tasks: HashMap::new(),
+        counters: HashMap::new(),
+        track_cpu_time: false,
+        warn_on_dump: false,
+        #[cfg(all(feature = "perf", target_os = "linux"))]
+        track_perf_counters: false,
     })
 });
 
+type TaskEvent = (
+    u128,               // start time
+    Option<u128>,       // end time (optional)
+    Map<String, Value>, // other description
+    TaskUsage,          // resource usage
+);
+
+/// Resource usage measured over the lifetime of a task
+#[derive(Debug, Default)]
+struct TaskUsage {
+    cpu_time: Option<(u128, Option<u128>)>, // thread CPU time at start & end (optional)
+    #[cfg(all(feature = "perf", target_os = "linux"))]
+    perf: Option<(perf::PerfValues, Option<perf::PerfValues>)>, // hardware counters at start & end (optional)
+}
+
 #[derive(Debug)]
 struct Profiler {
     genesis: Instant,
+    genesis_unix_nano: u128, // wall-clock time of `genesis` since the Unix epoch
     thread_tasks: HashMap<
         String, // thread id
         HashMap<
             String, // task name
-            Vec<(
-                u128,               // start time
-                Option<u128>,       // end time (optional)
-                Map<String, Value>, // other description
-            )>,
+            Vec<TaskEvent>,
         >,
     >,
     global_tasks: HashMap<
         String,       // task name
         (u128, bool), // occurrence count & is ended
     >,
+    counters: HashMap<
+        String,           // counter name
+        Vec<(u128, u64)>, // sample time & value
+    >,
+    track_cpu_time: bool,
+    warn_on_dump: bool,
+    #[cfg(all(feature = "perf", target_os = "linux"))]
+    track_perf_counters: bool,
 }
 
 impl Profiler {
@@ -172,13 +240,13 @@ impl Profiler {
 
     /// Gets a reference to the events vector for a specific task and thread
     /// Panics if either the thread or task don't exist
-    fn must_get(&self, task: &str, thread: &str) -> &Vec<(u128, Option<u128>, Map<String, Value>)> {
+    fn must_get(&self, task: &str, thread: &str) -> &Vec<TaskEvent> {
         self.thread_tasks.get(thread).unwrap().get(task).unwrap()
     }
 
     /// Gets a reference to the events vector for a specific task in the current thread
     /// Panics if either the thread or task don't exist
-    fn must_get_current(&self, task: &str) -> &Vec<(u128, Option<u128>, Map<String, Value>)> {
+    fn must_get_current(&self, task: &str) -> &Vec<TaskEvent> {
         self.thread_tasks
             .get(&Profiler::get_current_thread_name())
             .unwrap()
@@ -188,11 +256,7 @@ impl Profiler {
 
     /// Gets a mutable reference to the events vector for a specific task and thread
     /// Panics if either the thread or task don't exist
-    fn must_get_mut(
-        &mut self,
-        task: &str,
-        thread: &str,
-    ) -> &mut Vec<(u128, Option<u128>, Map<String, Value>)> {
+    fn must_get_mut(&mut self, task: &str, thread: &str) -> &mut Vec<TaskEvent> {
         self.thread_tasks
             .get_mut(thread)
             .unwrap()
@@ -202,10 +266,7 @@ impl Profiler {
 
     /// Gets a mutable reference to the events vector for a specific task in the current thread
     /// Panics if either the thread or task don't exist
-    fn must_get_mut_current(
-        &mut self,
-        task: &str,
-    ) -> &mut Vec<(u128, Option<u128>, Map<String, Value>)> {
+    fn must_get_mut_current(&mut self, task: &str) -> &mut Vec<TaskEvent> {
         self.thread_tasks
             .get_mut(&Profiler::get_current_thread_name())
             .unwrap()
@@ -213,12 +274,62 @@ impl Profiler {
             .unwrap()
     }
 
+    /// Starts measuring the resource usage of a task in the current thread
+    fn begin_usage(&self) -> TaskUsage {
+        TaskUsage {
+            cpu_time: if self.track_cpu_time {
+                thread_cpu_time().map(|cpu_time| (cpu_time, None))
+            } else {
+                None
+            },
+            #[cfg(all(feature = "perf", target_os = "linux"))]
+            perf: if self.track_perf_counters {
+                perf::read_counters().map(|values| (values, None))
+            } else {
+                None
+            },
+        }
+    }
+
     /// Clears all profiling data from the profiler
     fn clear(&mut self) {
         self.thread_tasks.clear();
+        self.counters.clear();
+    }
+}
+
+/// Reads the CPU time consumed by the current thread, in nanoseconds
+#[cfg(unix)]
+fn thread_cpu_time() -> Option<u128> {
+    let mut time = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    match unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut time) } {
+        0 => Some(time.tv_sec as u128 * 1_000_000_000 + time.tv_nsec as u128),
+        _ => None,
     }
 }
 
+/// Reads the CPU time consumed by the current thread, in nanoseconds
+#[cfg(not(unix))]
+fn thread_cpu_time() -> Option<u128> {
+    None
+}
+
+/// Returns the wall-clock time of the genesis, in nanoseconds since the Unix epoch.
+/// 
+/// Adding this to the (genesis-relative) timestamps of a dump converts them to
+/// absolute Unix timestamps.
+/// 
+/// # Returns
+/// 
+/// * `u128` - The initialization time of the profiler since the Unix epoch
+pub fn get_genesis_unix_nano() -> u128 {
+    let profiler = Profiler::global().lock().unwrap();
+    profiler.genesis_unix_nano
+}
+
 /// Returns the genesis time when the profiler was initialized.
 /// 
 /// This timestamp serves as the reference point for all timing measurements
@@ -232,6 +343,62 @@ pub fn get_genesis() -> Instant {
     profiler.genesis
 }
 
+/// Enables or disables measuring the thread CPU time of tasks.
+/// 
+/// When enabled, [`start()`] and [`end()`] additionally read the CPU time of the current
+/// thread (`CLOCK_THREAD_CPUTIME_ID`), and [`dump()`] emits a `"cpu_runtime"` field next to
+/// the wall-clock `"runtime"` of every task started while tracking was on. A task whose
+/// CPU time is much smaller than its wall time spent most of its life blocked, e.g.
+/// waiting on a lock. Tasks of open (un-ended) events report `"cpu_runtime": null`.
+/// 
+/// CPU time is only measured for thread-specific tasks, since [`start_multi()`] and
+/// [`end_multi()`] may be called from different threads. Tracking is disabled by default
+/// and unavailable on non-unix platforms.
+/// 
+/// # Arguments
+/// 
+/// * `enabled` - Whether tasks started from now on should record their CPU time
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use altius_benchtools::profiler;
+/// 
+/// profiler::enable_cpu_time(true);
+/// profiler::start("locked_section");
+/// // ... acquire a lock and perform work ...
+/// profiler::end("locked_section");
+/// ```
+pub fn enable_cpu_time(enabled: bool) {
+    let mut profiler = Profiler::global().lock().unwrap();
+    profiler.track_cpu_time = enabled;
+}
+
+/// Enables or disables warnings about unbalanced or leaked tasks on dump.
+/// 
+/// When enabled, every dump ([`dump()`], [`dump_json()`], [`dump_zip()`] and their
+/// [`dump_with()`] variants) runs [`validate()`] on the dumped data and prints a warning to
+/// stderr for every task that was never ended and every suspiciously long span. Summaries,
+/// analyses and the other exporters do not warn. Warnings are disabled by default.
+/// 
+/// # Arguments
+/// 
+/// * `enabled` - Whether dumps should print validation warnings
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use altius_benchtools::profiler;
+/// 
+/// profiler::enable_dump_warnings(true);
+/// profiler::start("leaked");
+/// profiler::dump_json("profile_results.json"); // warns that "leaked" was never ended
+/// ```
+pub fn enable_dump_warnings(enabled: bool) {
+    let mut profiler = Profiler::global().lock().unwrap();
+    profiler.warn_on_dump = enabled;
+}
+
 /// Starts timing a new task in the current thread.
 /// 
 /// This function begins tracking a new task's execution time. Each task must be ended
@@ -259,17 +426,18 @@ pub fn get_genesis() -> Instant {
 pub fn start(task: &str) {
     let mut profiler = Profiler::global().lock().unwrap();
     let genesis = profiler.genesis;
-    match profiler.insert_current_thread_task(task) {
-        false => assert!(
+    if !profiler.insert_current_thread_task(task) {
+        assert!(
             profiler.must_get_current(task).last().unwrap().1.is_some(),
             "the last event must be end"
-        ),
-        true => (),
-    };
+        );
+    }
+    let usage = profiler.begin_usage();
     profiler.must_get_mut_current(task).push((
         Instant::now().duration_since(genesis).as_nanos(),
         None,
         Map::new(),
+        usage,
     ));
 }
 
@@ -324,17 +492,17 @@ pub fn start_multi(base_task: &str) {
         }
     };
     let task = &format!("{}-[{}]", base_task, count);
-    match profiler.insert_thread_task(task, "main") {
-        false => assert!(
+    if !profiler.insert_thread_task(task, "main") {
+        assert!(
             profiler.must_get(task, "main").last().unwrap().1.is_some(),
             "the last event must be end"
-        ),
-        true => (),
-    };
+        );
+    }
     profiler.must_get_mut(task, "main").push((
         Instant::now().duration_since(genesis).as_nanos(),
         None,
         Map::new(),
+        TaskUsage::default(),
     ));
 }
 
@@ -367,8 +535,16 @@ pub fn end(task: &str) {
         profiler.must_get_current(task).last().unwrap().1.is_none(),
         "the last event must be start"
     );
-    profiler.must_get_mut_current(task).last_mut().unwrap().1 =
-        Some(Instant::now().duration_since(profiler.genesis).as_nanos());
+    let now = Instant::now().duration_since(profiler.genesis).as_nanos();
+    let event = profiler.must_get_mut_current(task).last_mut().unwrap();
+    event.1 = Some(now);
+    if let Some((_, cpu_end)) = &mut event.3.cpu_time {
+        *cpu_end = thread_cpu_time();
+    }
+    #[cfg(all(feature = "perf", target_os = "linux"))]
+    if let Some((_, perf_end)) = &mut event.3.perf {
+        *perf_end = perf::read_counters();
+    }
 }
 
 /// Ends timing for a task that was called multiple times.
@@ -630,6 +806,9 @@ pub fn note_str_multi(base_task: &str, key: &str, value: &str) {
 /// This is an unchecked version of [`note_str()`] that will create a new task entry
 /// if one doesn't exist. Use with caution as it bypasses the normal start/end task flow.
 /// 
+/// A task entry created by a note is a point annotation: it starts and ends at the time
+/// of the note, so it is never reported as a leaked task by [`validate()`].
+/// 
 /// # Arguments
 /// 
 /// * `task` - The string identifier of the task to annotate
@@ -640,7 +819,7 @@ pub fn note_str_multi(base_task: &str, key: &str, value: &str) {
 /// 
 /// This function is marked as unchecked because it:
 /// - Does not verify if the task exists
-/// - Creates a new task entry if none exists
+/// - Creates a new, already ended task entry if none exists
 /// - Does not enforce the normal start/end task flow
 /// 
 /// # Examples
@@ -654,14 +833,15 @@ pub fn note_str_multi(base_task: &str, key: &str, value: &str) {
 pub fn note_str_unchecked(task: &str, key: &str, value: &str) {
     let mut profiler = Profiler::global().lock().unwrap();
     let genesis = profiler.genesis;
-    match profiler.insert_thread_task(task, "main") {
-        true => profiler.must_get_mut(task, "main").push((
-            Instant::now().duration_since(genesis).as_nanos(),
-            None,
+    if profiler.insert_thread_task(task, "main") {
+        let now = Instant::now().duration_since(genesis).as_nanos();
+        profiler.must_get_mut(task, "main").push((
+            now,
+            Some(now),
             Map::new(),
-        )),
-        false => (),
-    };
+            TaskUsage::default(),
+        ));
+    }
     profiler
         .must_get_mut(task, "main")
         .last_mut()
@@ -670,10 +850,40 @@ pub fn note_str_unchecked(task: &str, key: &str, value: &str) {
         .insert(key.to_string(), Value::String(value.to_string()));
 }
 
+/// Records a sample of a named counter at the current time.
+/// 
+/// Counters are process-wide tracks (memory usage, queue depth, bytes written, ...)
+/// that are emitted next to the task timeline in [`dump()`]. Each call appends one
+/// sample; the timestamp is relative to the profiler's genesis time.
+/// 
+/// # Arguments
+/// 
+/// * `name` - The name of the counter track
+/// * `value` - The value of the counter at this point in time
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use altius_benchtools::profiler;
+/// 
+/// profiler::counter("pending_txs", 128);
+/// // ... process some transactions ...
+/// profiler::counter("pending_txs", 64);
+/// ```
+pub fn counter(name: &str, value: u64) {
+    let mut profiler = Profiler::global().lock().unwrap();
+    let now = Instant::now().duration_since(profiler.genesis).as_nanos();
+    profiler
+        .counters
+        .entry(name.to_string())
+        .or_default()
+        .push((now, value));
+}
+
 /// Clears all profiling data from the profiler.
 /// 
-/// This function removes all recorded tasks, events, and their associated metadata
-/// from the profiler. The genesis time is preserved.
+/// This function removes all recorded tasks, events, counter samples and their
+/// associated metadata from the profiler. The genesis time is preserved.
 /// 
 /// # Examples
 /// 
@@ -694,6 +904,14 @@ pub fn clear() {
 /// includes timing information, thread identification, custom annotations, and special
 /// event types (transactions, commits).
 /// 
+/// If any counters were recorded (see [`counter()`]), the per-thread arrays are followed
+/// by a `{ "counters": [...] }` object holding their samples as `"counter"` events, each
+/// with `start == end` set to the sample time and the sample stored under `"value"`.
+/// Tasks measured while [`enable_cpu_time()`] was on additionally carry a `"cpu_runtime"` field, and with the
+/// `perf` feature, tasks measured with `enable_perf_counters()` carry a `"perf"` object.
+/// 
+/// Use [`dump_with()`] for compact output, filters or time windows.
+/// 
 /// # Returns
 /// 
 /// * `String` - A pretty-printed JSON string containing all profiling data
@@ -731,21 +949,38 @@ pub fn clear() {
 /// }
 /// ```
 pub fn dump() -> String {
+    dump_with(&DumpOptions::default())
+}
+
+/// Key of the object holding the counter samples of a dump, after the per-thread arrays
+const COUNTERS_KEY: &str = "counters";
+
+/// Returns the counter samples of a dump, empty if it recorded none
+fn dump_counters(dump: &Value) -> &[Value] {
+    dump.as_array()
+        .into_iter()
+        .flatten()
+        .find_map(|entry| entry[COUNTERS_KEY].as_array())
+        .map_or(&[], Vec::as_slice)
+}
+
+/// Builds the dump of [`dump()`] as a JSON value
+fn dump_value() -> Value {
     let profiler = Profiler::global().lock().unwrap();
     let now = Instant::now().duration_since(profiler.genesis).as_nanos();
 
     let mut output_frontend = Value::Array(vec![]);
 
-    for (_thread_name, thread_events) in &profiler.thread_tasks {
+    for thread_events in profiler.thread_tasks.values() {
         let mut detail = vec![];
         for (name, thread_tasks) in thread_events {
             for event in thread_tasks {
-                let (start, end_opt, description) = event;
+                let (start, end_opt, description, usage) = event;
                 let duration = end_opt.unwrap_or(now) - start;
 
-                match description.get("type") {
+                let mut entry = match description.get("type") {
                     Some(Value::String(type_str)) => match type_str.as_str() {
-                        "transaction" => detail.push(json!({
+                        "transaction" => json!({
                             "type": "transaction",
                             "tx": name,
                             "runtime": duration,
@@ -757,8 +992,8 @@ pub fn dump() -> String {
                                 None => "unknown",
                             },
                             "detail": description,
-                        })),
-                        "commit" => detail.push(json!({
+                        }),
+                        "commit" => json!({
                             "type": "commit",
                             "tx": match description.get("tx") {
                                 Some(value) => value.as_str().unwrap_or("unknown"),
@@ -768,25 +1003,36 @@ pub fn dump() -> String {
                             "start": start,
                             "end": end_opt,
                             "detail": description,
-                        })),
-                        other_type => detail.push(json!({
+                        }),
+                        other_type => json!({
                             "type": other_type,
                             "name": name,
                             "runtime": duration,
                             "start": start,
                             "end": end_opt,
                             "detail": description,
-                        })),
+                        }),
                     },
-                    _ => detail.push(json!({
+                    _ => json!({
                         "type": "other",
                         "name": name,
                         "runtime": duration,
                         "start": start,
                         "end": end_opt,
                         "detail": description,
-                    })),
+                    }),
+                };
+
+                if let Some((cpu_start, cpu_end)) = usage.cpu_time {
+                    entry["cpu_runtime"] = json!(cpu_end.map(|cpu_end| cpu_end - cpu_start));
+                }
+                #[cfg(all(feature = "perf", target_os = "linux"))]
+                if let Some((perf_start, perf_end)) = &usage.perf {
+                    entry["perf"] = json!(perf_end
+                        .as_ref()
+                        .map(|perf_end| perf::delta(perf_start, perf_end)));
                 }
+                detail.push(entry);
             }
         }
         output_frontend
@@ -795,7 +1041,29 @@ pub fn dump() -> String {
             .push(Value::Array(detail));
     }
 
-    serde_json::to_string_pretty(&output_frontend).unwrap()
+    if !profiler.counters.is_empty() {
+        let mut samples = vec![];
+        for (name, counter_samples) in &profiler.counters {
+            for (time, value) in counter_samples {
+                samples.push(json!({
+                    "type": "counter",
+                    "name": name,
+                    "runtime": 0,
+                    "start": time,
+                    "end": time,
+                    "value": value,
+                    "detail": {},
+                }));
+            }
+        }
+        samples.sort_by_key(|sample| sample["start"].as_u64());
+        output_frontend
+            .as_array_mut()
+            .unwrap()
+            .push(json!({ COUNTERS_KEY: samples }));
+    }
+
+    output_frontend
 }
 
 /// Dumps the profiler data to a JSON file at the specified path.
@@ -849,14 +1117,18 @@ pub fn dump_json(output_path: &str) {
 /// // Creates profile_results.zip containing profile_results.json
 /// ```
 pub fn dump_zip(output_name: &str) {
-    let result_json = dump();
-    let file = File::create(output_name.to_string() + ".zip").unwrap();
+    write_zip(output_name, output_name, &dump()).unwrap();
+}
+
+/// Writes `content` as `{entry_name}.json` into a new `{output_name}.zip` archive
+fn write_zip(output_name: &str, entry_name: &str, content: &str) -> zip::result::ZipResult<()> {
+    let file = File::create(output_name.to_string() + ".zip")?;
     let mut zip = ZipWriter::new(BufWriter::new(file));
     let options = FileOptions::<()>::default().compression_method(CompressionMethod::Deflated);
-    zip.start_file(output_name.to_string() + ".json", options)
-        .unwrap();
-    zip.write_all(result_json.as_bytes()).unwrap();
-    zip.finish().unwrap();
+    zip.start_file(entry_name.to_string() + ".json", options)?;
+    zip.write_all(content.as_bytes())?;
+    zip.finish()?;
+    Ok(())
 }
 
 /// Prints the current state of the profiler for debugging purposes.
@@ -0,0 +1,391 @@
+//! Parallel-execution analytics over `"transaction"` and `"commit"` events.
+//!
+//! The analysis treats every thread of a dump that recorded transaction or commit events
+//! as a worker of a parallel executor, and every transaction event as one execution of the
+//! transaction named by its `"tx"` field. Transactions are matched with their commit event
+//! through the commit's `"tx"` field. Open (un-ended) events are ignored.
+//!
+//! Definitions used in the report (all durations in nanoseconds):
+//!
+//! - **makespan**: from the first start to the last end of any transaction/commit event
+//! - **parallelism**: number of executions running at the same time, averaged over the
+//!   makespan (`average`) and over [`PARALLELISM_BUCKETS`] equal time buckets (`timeline`)
+//! - **utilisation** of a worker: time covered by its events divided by the makespan
+//! - **idle gaps** of a worker: gaps between two consecutive events of the worker
+//! - **re-executions** of a transaction: executions beyond the first; every execution but
+//!   the last is counted as aborted
+//! - **commit latency**: commit end minus the end of the transaction's last execution
+//! - **sequential time**: the last execution plus the commit of every transaction, i.e.
+//!   the work of an executor that never aborts, run on a single thread
+//! - **critical path**: the longest chain of work when commits happen one after another in
+//!   commit order and every commit waits for all executions of its transaction:
+//!   `cp(i) = max(cp(i - 1), executions(i)) + commit(i)`
+//! - **speedup**: sequential time divided by the makespan (`observed`) or by the critical
+//!   path (`theoretical`)
+
+use super::dump_value;
+use serde_json::{json, Value};
+use std::{collections::BTreeMap, fmt::Write as _};
+
+/// Number of time buckets of the parallelism timeline
+pub const PARALLELISM_BUCKETS: usize = 100;
+
+/// A closed interval of a transaction or commit event
+#[derive(Clone, Copy)]
+struct Span {
+    start: u64,
+    end: u64,
+}
+
+impl Span {
+    fn of(event: &Value) -> Option<Span> {
+        let start = event["start"].as_u64()?;
+        let end = event["end"].as_u64()?;
+        Some(Span { start, end })
+    }
+
+    fn runtime(&self) -> u64 {
+        self.end - self.start
+    }
+}
+
+/// Returns `(count, total, max)` of a list of durations
+fn totals(durations: &[u64]) -> (usize, u64, u64) {
+    let total = durations.iter().sum();
+    let max = durations.iter().copied().max().unwrap_or(0);
+    (durations.len(), total, max)
+}
+
+/// Returns the value at the given quantile of a sorted list (nearest rank), or 0 if empty
+fn quantile(sorted: &[u64], quantile: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (quantile * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Analyzes the parallel execution recorded in a dump produced by [`dump()`](super::dump).
+///
+/// See the [module documentation](self) for the definitions of the reported metrics.
+///
+/// # Arguments
+///
+/// * `dump` - The parsed output of [`dump()`](super::dump)
+///
+/// # Returns
+///
+/// * `Value` - The analysis report with the following structure:
+///
+/// ```json
+/// {
+///   "makespan": 2500000,
+///   "transactions": 1000,
+///   "executions": 1100,
+///   "re_executions": 100,
+///   "open_events": 0,
+///   "parallelism": { "average": 7.6, "timeline": [{ "start": 0, "parallelism": 7.9 }] },
+///   "workers": [{ "thread": 0, "events": 140, "busy": 2300000, "utilisation": 0.92,
+///                 "idle_gaps": { "count": 139, "total": 200000, "max": 9000 } }],
+///   "critical_path": 600000,
+///   "sequential_time": 19000000,
+///   "speedup": { "observed": 7.6, "theoretical": 31.6 },
+///   "commit_latency": { "count": 1000, "mean": 5000, "p50": 4000, "p95": 12000, "max": 40000 },
+///   "per_transaction": { "0x01": { "executions": 2, "re_executions": 1, "statuses": { "success": 2 },
+///                                  "commit_latency": 4100 } }
+/// }
+/// ```
+pub fn analyze(dump: &Value) -> Value {
+    let mut open_events = 0;
+    let mut executions: BTreeMap<String, Vec<(Span, String)>> = BTreeMap::new();
+    let mut commits: Vec<(String, Span)> = vec![];
+    let mut workers: Vec<(usize, Vec<Span>)> = vec![];
+
+    for (thread_idx, thread) in dump
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_array)
+        .enumerate()
+    {
+        let mut worker_spans = vec![];
+        for event in thread {
+            let event_type = event["type"].as_str().unwrap_or_default();
+            if event_type != "transaction" && event_type != "commit" {
+                continue;
+            }
+            let Some(span) = Span::of(event) else {
+                open_events += 1;
+                continue;
+            };
+            worker_spans.push(span);
+            let tx = event["tx"].as_str().unwrap_or("unknown").to_string();
+            if event_type == "transaction" {
+                let status = event["status"].as_str().unwrap_or("unknown").to_string();
+                executions.entry(tx).or_default().push((span, status));
+            } else {
+                commits.push((tx, span));
+            }
+        }
+        if !worker_spans.is_empty() {
+            worker_spans.sort_by_key(|span| span.start);
+            workers.push((thread_idx, worker_spans));
+        }
+    }
+
+    let all_spans = || workers.iter().flat_map(|(_, spans)| spans);
+    let first_start = all_spans().map(|span| span.start).min().unwrap_or(0);
+    let last_end = all_spans().map(|span| span.end).max().unwrap_or(0);
+    let makespan = last_end - first_start;
+
+    // parallelism of executions, overall and per bucket
+    let execution_spans = || executions.values().flatten().map(|(span, _)| span);
+    let execution_time: u64 = execution_spans().map(Span::runtime).sum();
+    let bucket_width = makespan.div_ceil(PARALLELISM_BUCKETS as u64).max(1);
+    let mut buckets = vec![0u64; PARALLELISM_BUCKETS];
+    for span in execution_spans() {
+        let (mut start, end) = (span.start - first_start, span.end - first_start);
+        while start < end {
+            let bucket = ((start / bucket_width) as usize).min(PARALLELISM_BUCKETS - 1);
+            let bucket_end = (bucket as u64 + 1) * bucket_width;
+            buckets[bucket] += end.min(bucket_end) - start;
+            start = end.min(bucket_end);
+        }
+    }
+    let ratio = |numerator: u64, denominator: u64| match denominator {
+        0 => 0.0,
+        _ => numerator as f64 / denominator as f64,
+    };
+    let timeline: Vec<Value> = match makespan {
+        0 => vec![],
+        _ => buckets
+            .iter()
+            .enumerate()
+            .map(|(bucket, busy)| {
+                json!({
+                    "start": bucket as u64 * bucket_width,
+                    "parallelism": ratio(*busy, bucket_width),
+                })
+            })
+            .collect(),
+    };
+
+    // utilisation and idle gaps per worker
+    let worker_reports: Vec<Value> = workers
+        .iter()
+        .map(|(thread_idx, spans)| {
+            let mut busy = 0;
+            let mut covered_until = spans[0].start;
+            let mut gaps = vec![];
+            for span in spans {
+                if span.start > covered_until {
+                    gaps.push(span.start - covered_until);
+                }
+                busy += span.end.saturating_sub(covered_until.max(span.start));
+                covered_until = covered_until.max(span.end);
+            }
+            let (count, total, max) = totals(&gaps);
+            json!({
+                "thread": thread_idx,
+                "events": spans.len(),
+                "busy": busy,
+                "utilisation": ratio(busy, makespan),
+                "idle_gaps": { "count": count, "total": total, "max": max },
+            })
+        })
+        .collect();
+
+    // commit latency, critical path and sequential time
+    commits.sort_by_key(|(_, span)| span.end);
+    let mut latencies = BTreeMap::new();
+    let mut critical_path = 0;
+    for (tx, commit) in &commits {
+        let tx_executions = executions.get(tx).map(Vec::as_slice).unwrap_or_default();
+        if let Some(last_end) = tx_executions.iter().map(|(span, _)| span.end).max() {
+            latencies.insert(tx.clone(), commit.end.saturating_sub(last_end));
+        }
+        let execution_chain: u64 = tx_executions.iter().map(|(span, _)| span.runtime()).sum();
+        critical_path = critical_path.max(execution_chain) + commit.runtime();
+    }
+    let sequential_time: u64 = executions
+        .values()
+        .filter_map(|runs| runs.iter().max_by_key(|(span, _)| span.end))
+        .map(|(span, _)| span.runtime())
+        .sum::<u64>()
+        + commits.iter().map(|(_, span)| span.runtime()).sum::<u64>();
+    if commits.is_empty() {
+        critical_path = executions
+            .values()
+            .map(|runs| runs.iter().map(|(span, _)| span.runtime()).sum())
+            .max()
+            .unwrap_or(0);
+    }
+
+    let mut sorted_latencies: Vec<u64> = latencies.values().copied().collect();
+    sorted_latencies.sort_unstable();
+    let (latency_count, latency_total, latency_max) = totals(&sorted_latencies);
+
+    let total_executions: usize = executions.values().map(Vec::len).sum();
+    let per_transaction: serde_json::Map<String, Value> = executions
+        .iter()
+        .map(|(tx, runs)| {
+            let mut statuses: BTreeMap<&str, u64> = BTreeMap::new();
+            for (_, status) in runs {
+                *statuses.entry(status).or_default() += 1;
+            }
+            let report = json!({
+                "executions": runs.len(),
+                "re_executions": runs.len() - 1,
+                "statuses": statuses,
+                "commit_latency": latencies.get(tx),
+            });
+            (tx.clone(), report)
+        })
+        .collect();
+
+    json!({
+        "makespan": makespan,
+        "transactions": executions.len(),
+        "executions": total_executions,
+        "re_executions": total_executions - executions.len(),
+        "open_events": open_events,
+        "parallelism": {
+            "average": ratio(execution_time, makespan),
+            "timeline": timeline,
+        },
+        "workers": worker_reports,
+        "critical_path": critical_path,
+        "sequential_time": sequential_time,
+        "speedup": {
+            "observed": ratio(sequential_time, makespan),
+            "theoretical": ratio(sequential_time, critical_path),
+        },
+        "commit_latency": {
+            "count": latency_count,
+            "mean": if latency_count == 0 { 0 } else { latency_total / latency_count as u64 },
+            "p50": quantile(&sorted_latencies, 0.5),
+            "p95": quantile(&sorted_latencies, 0.95),
+            "max": latency_max,
+        },
+        "per_transaction": per_transaction,
+    })
+}
+
+/// Returns the parallel-execution analysis of the current profiling data as a JSON string.
+///
+/// This is [`analyze()`] applied to the current [`dump()`](super::dump).
+///
+/// # Returns
+///
+/// * `String` - A pretty-printed JSON string containing the analysis report
+///
+/// # Examples
+///
+/// ```rust
+/// use altius_benchtools::profiler;
+///
+/// profiler::start("0x01");
+/// profiler::note_str("0x01", "type", "transaction");
+/// profiler::note_str("0x01", "status", "success");
+/// profiler::end("0x01");
+///
+/// println!("{}", profiler::analysis());
+/// ```
+pub fn analysis() -> String {
+    serde_json::to_string_pretty(&analyze(&dump_value())).unwrap()
+}
+
+/// Renders an analysis report produced by [`analyze()`] as human-readable text.
+///
+/// # Arguments
+///
+/// * `report` - The analysis report
+///
+/// # Returns
+///
+/// * `String` - A multi-line text report, durations in microseconds
+///
+/// # Examples
+///
+/// ```rust
+/// use altius_benchtools::profiler;
+///
+/// let dump = serde_json::from_str(&profiler::dump()).unwrap();
+/// println!("{}", profiler::analysis_text(&profiler::analyze(&dump)));
+/// ```
+pub fn analysis_text(report: &Value) -> String {
+    let micros = |value: &Value| value.as_u64().unwrap_or(0) as f64 / 1000.0;
+    let mut text = String::new();
+    let _ = writeln!(text, "Parallel execution analysis");
+    let _ = writeln!(
+        text,
+        "  makespan:            {:>12.1} us",
+        micros(&report["makespan"])
+    );
+    let _ = writeln!(
+        text,
+        "  transactions:        {:>12} ({} executions, {} re-executions, {} open events)",
+        report["transactions"],
+        report["executions"],
+        report["re_executions"],
+        report["open_events"]
+    );
+    let _ = writeln!(
+        text,
+        "  avg parallelism:     {:>12.2}",
+        report["parallelism"]["average"].as_f64().unwrap_or(0.0)
+    );
+    let _ = writeln!(
+        text,
+        "  critical path:       {:>12.1} us",
+        micros(&report["critical_path"])
+    );
+    let _ = writeln!(
+        text,
+        "  sequential time:     {:>12.1} us",
+        micros(&report["sequential_time"])
+    );
+    let _ = writeln!(
+        text,
+        "  speedup:             {:>12.2}x observed, {:.2}x theoretical",
+        report["speedup"]["observed"].as_f64().unwrap_or(0.0),
+        report["speedup"]["theoretical"].as_f64().unwrap_or(0.0)
+    );
+    let latency = &report["commit_latency"];
+    let _ = writeln!(
+        text,
+        "  commit latency:      {:>12.1} us mean, {:.1} p50, {:.1} p95, {:.1} max ({} commits)",
+        micros(&latency["mean"]),
+        micros(&latency["p50"]),
+        micros(&latency["p95"]),
+        micros(&latency["max"]),
+        latency["count"]
+    );
+    let _ = writeln!(text, "Workers");
+    for worker in report["workers"].as_array().into_iter().flatten() {
+        let _ = writeln!(
+            text,
+            "  thread {:>3}: {:>6} events, {:>5.1}% utilised, {} idle gaps ({:.1} us total, {:.1} us max)",
+            worker["thread"],
+            worker["events"],
+            worker["utilisation"].as_f64().unwrap_or(0.0) * 100.0,
+            worker["idle_gaps"]["count"],
+            micros(&worker["idle_gaps"]["total"]),
+            micros(&worker["idle_gaps"]["max"])
+        );
+    }
+    let mut most_re_executed: Vec<(&String, &Value)> = report["per_transaction"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(_, tx)| tx["re_executions"].as_u64().unwrap_or(0) > 0)
+        .collect();
+    most_re_executed.sort_by_key(|(_, tx)| std::cmp::Reverse(tx["re_executions"].as_u64()));
+    if !most_re_executed.is_empty() {
+        let _ = writeln!(text, "Most re-executed transactions");
+        for (tx, stats) in most_re_executed.iter().take(10) {
+            let _ = writeln!(text, "  {}: {} re-executions", tx, stats["re_executions"]);
+        }
+    }
+    text
+}
@@ -0,0 +1,443 @@
+//! Compact binary encoding of profiling dumps.
+//!
+//! The binary format stores exactly the same data as the JSON output of
+//! [`dump()`](super::dump), so a dump can be converted back and forth without loss, but it
+//! is several times smaller and faster to write and parse for large traces.
+//!
+//! Layout (all integers are unsigned LEB128 varints unless noted otherwise):
+//!
+//! ```text
+//! magic         b"ABTP"
+//! version       u8 (currently 1)
+//! strings       count, then for each string: byte length, UTF-8 bytes
+//! threads       count, then for each thread:
+//!   events      count, then for each event: byte length, value
+//! counters      count, then for each sample: byte length, value
+//! ```
+//!
+//! Every string of the dump (task names, note keys and string values) is stored once in
+//! the string table and referenced by its index. An event is a length-prefixed record, so
+//! readers can skip events without decoding them. Values are encoded with a one-byte tag:
+//!
+//! | tag | value                                                 |
+//! |-----|-------------------------------------------------------|
+//! | 0   | `null`                                                |
+//! | 1   | `false`                                               |
+//! | 2   | `true`                                                |
+//! | 3   | unsigned integer (varint)                             |
+//! | 4   | negative integer (zigzag varint)                      |
+//! | 5   | float (8 bytes, little endian)                        |
+//! | 6   | string (string table index)                           |
+//! | 7   | array (length, values)                                |
+//! | 8   | object (length, then key string index and value pairs) |
+//!
+//! Arrays and objects nest at most [`MAX_DEPTH`] levels deep, as in the JSON dumps.
+
+use super::{dump_counters, dump_value, COUNTERS_KEY};
+use serde_json::{json, Map, Number, Value};
+use std::{
+    collections::HashMap,
+    fs,
+    hash::{BuildHasherDefault, Hasher},
+    io::{Error, ErrorKind},
+};
+
+const MAGIC: &[u8; 4] = b"ABTP";
+const VERSION: u8 = 1;
+
+const TAG_NULL: u8 = 0;
+const TAG_FALSE: u8 = 1;
+const TAG_TRUE: u8 = 2;
+const TAG_UINT: u8 = 3;
+const TAG_INT: u8 = 4;
+const TAG_FLOAT: u8 = 5;
+const TAG_STRING: u8 = 6;
+const TAG_ARRAY: u8 = 7;
+const TAG_OBJECT: u8 = 8;
+
+/// Maximum nesting depth of arrays and objects in an event, the recursion limit of
+/// serde_json, which bounds the stack used to decode untrusted input
+const MAX_DEPTH: usize = 128;
+
+fn write_varint(buffer: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buffer.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buffer.push(value as u8);
+}
+
+/// Word-at-a-time multiplicative hasher, much faster than the default SipHash for the
+/// many short strings of a dump (interning is not exposed to untrusted input)
+#[derive(Default)]
+struct FastHasher(u64);
+
+impl Hasher for FastHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.add(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        for byte in chunks.remainder() {
+            self.add(*byte as u64);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl FastHasher {
+    fn add(&mut self, word: u64) {
+        self.0 = (self.0.rotate_left(5) ^ word).wrapping_mul(0x517c_c1b7_2722_0a95);
+    }
+}
+
+/// Encodes values while collecting the strings they reference into a string table
+#[derive(Default)]
+struct Encoder<'a> {
+    strings: Vec<&'a str>,
+    indices: HashMap<&'a str, u64, BuildHasherDefault<FastHasher>>,
+}
+
+impl<'a> Encoder<'a> {
+    fn intern(&mut self, string: &'a str) -> u64 {
+        let next = self.strings.len() as u64;
+        let index = *self.indices.entry(string).or_insert(next);
+        if index == next {
+            self.strings.push(string);
+        }
+        index
+    }
+
+    fn encode(&mut self, buffer: &mut Vec<u8>, value: &'a Value) {
+        match value {
+            Value::Null => buffer.push(TAG_NULL),
+            Value::Bool(false) => buffer.push(TAG_FALSE),
+            Value::Bool(true) => buffer.push(TAG_TRUE),
+            Value::Number(number) => {
+                if let Some(unsigned) = number.as_u64() {
+                    buffer.push(TAG_UINT);
+                    write_varint(buffer, unsigned);
+                } else if let Some(signed) = number.as_i64() {
+                    buffer.push(TAG_INT);
+                    write_varint(buffer, ((signed << 1) ^ (signed >> 63)) as u64);
+                } else {
+                    buffer.push(TAG_FLOAT);
+                    buffer.extend_from_slice(&number.as_f64().unwrap_or(0.0).to_le_bytes());
+                }
+            }
+            Value::String(string) => {
+                buffer.push(TAG_STRING);
+                let index = self.intern(string);
+                write_varint(buffer, index);
+            }
+            Value::Array(values) => {
+                buffer.push(TAG_ARRAY);
+                write_varint(buffer, values.len() as u64);
+                for value in values {
+                    self.encode(buffer, value);
+                }
+            }
+            Value::Object(map) => {
+                buffer.push(TAG_OBJECT);
+                write_varint(buffer, map.len() as u64);
+                for (key, value) in map {
+                    let index = self.intern(key);
+                    write_varint(buffer, index);
+                    self.encode(buffer, value);
+                }
+            }
+        }
+    }
+}
+
+/// Reads values from a binary dump, failing on truncated or malformed input
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    strings: Vec<String>,
+}
+
+fn invalid(message: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("invalid binary dump: {}", message),
+    )
+}
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let end = self
+            .position
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| invalid("unexpected end of data"))?;
+        let slice = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(slice)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn read_varint(&mut self) -> Result<u64, Error> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.read_u8()?;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(invalid("varint too long"))
+    }
+
+    fn read_len(&mut self) -> Result<usize, Error> {
+        let len = self.read_varint()? as usize;
+        // every element takes at least one byte, which bounds preallocation on bad input
+        if len > self.bytes.len() - self.position {
+            return Err(invalid("length exceeds data"));
+        }
+        Ok(len)
+    }
+
+    fn read_string(&mut self) -> Result<String, Error> {
+        let index = self.read_varint()? as usize;
+        self.strings
+            .get(index)
+            .cloned()
+            .ok_or_else(|| invalid("string index out of range"))
+    }
+
+    /// Reads a value nested in `depth` arrays and objects
+    fn decode(&mut self, depth: usize) -> Result<Value, Error> {
+        let tag = self.read_u8()?;
+        if (tag == TAG_ARRAY || tag == TAG_OBJECT) && depth >= MAX_DEPTH {
+            return Err(invalid("nesting too deep"));
+        }
+        Ok(match tag {
+            TAG_NULL => Value::Null,
+            TAG_FALSE => Value::Bool(false),
+            TAG_TRUE => Value::Bool(true),
+            TAG_UINT => Value::from(self.read_varint()?),
+            TAG_INT => {
+                let zigzag = self.read_varint()?;
+                Value::from((zigzag >> 1) as i64 ^ -((zigzag & 1) as i64))
+            }
+            TAG_FLOAT => {
+                let bytes = self.take(8)?.try_into().unwrap();
+                Number::from_f64(f64::from_le_bytes(bytes))
+                    .map(Value::Number)
+                    .unwrap_or(Value::Null)
+            }
+            TAG_STRING => Value::String(self.read_string()?),
+            TAG_ARRAY => {
+                let len = self.read_len()?;
+                let mut values = Vec::with_capacity(len);
+                for _ in 0..len {
+                    values.push(self.decode(depth + 1)?);
+                }
+                Value::Array(values)
+            }
+            TAG_OBJECT => {
+                let len = self.read_len()?;
+                let mut map = Map::new();
+                for _ in 0..len {
+                    let key = self.read_string()?;
+                    map.insert(key, self.decode(depth + 1)?);
+                }
+                Value::Object(map)
+            }
+            tag => return Err(invalid(&format!("unknown value tag {}", tag))),
+        })
+    }
+
+    /// Reads a count followed by that many length-prefixed events
+    fn decode_events(&mut self) -> Result<Vec<Value>, Error> {
+        let event_count = self.read_len()?;
+        let mut events = Vec::with_capacity(event_count);
+        for _ in 0..event_count {
+            let len = self.read_len()?;
+            let end = self.position + len;
+            events.push(self.decode(0)?);
+            if self.position != end {
+                return Err(invalid("event length mismatch"));
+            }
+        }
+        Ok(events)
+    }
+}
+
+/// Encodes a dump produced by [`dump()`](super::dump) in the compact binary format.
+///
+/// The dump must be an array of per-thread event arrays, optionally holding a
+/// `{ "counters": [...] }` object, as produced by [`dump()`](super::dump); events
+/// themselves may be arbitrary JSON values.
+///
+/// # Arguments
+///
+/// * `dump` - The parsed output of [`dump()`](super::dump)
+///
+/// # Returns
+///
+/// * `Vec<u8>` - The binary encoding, readable with [`from_binary()`]
+///
+/// # Examples
+///
+/// ```rust
+/// use altius_benchtools::profiler;
+///
+/// let json = profiler::dump();
+/// let dump: serde_json::Value = serde_json::from_str(&json).unwrap();
+/// let binary = profiler::to_binary(&dump);
+/// assert_eq!(profiler::from_binary(&binary).unwrap(), dump);
+/// ```
+pub fn to_binary(dump: &Value) -> Vec<u8> {
+    let mut encoder = Encoder::default();
+    let mut body = vec![];
+    let threads: Vec<&Vec<Value>> = dump
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_array)
+        .collect();
+    write_varint(&mut body, threads.len() as u64);
+    let mut record = vec![];
+    for events in threads
+        .into_iter()
+        .map(Vec::as_slice)
+        .chain([dump_counters(dump)])
+    {
+        write_varint(&mut body, events.len() as u64);
+        for event in events {
+            record.clear();
+            encoder.encode(&mut record, event);
+            write_varint(&mut body, record.len() as u64);
+            body.extend_from_slice(&record);
+        }
+    }
+
+    let mut output = Vec::with_capacity(body.len() + 1024);
+    output.extend_from_slice(MAGIC);
+    output.push(VERSION);
+    write_varint(&mut output, encoder.strings.len() as u64);
+    for string in encoder.strings {
+        write_varint(&mut output, string.len() as u64);
+        output.extend_from_slice(string.as_bytes());
+    }
+    output.extend_from_slice(&body);
+    output
+}
+
+/// Decodes a binary dump produced by [`to_binary()`] or [`dump_binary()`].
+///
+/// The result is identical to the JSON dump it was encoded from, and can be passed to
+/// any function taking a parsed dump, or written back out as JSON.
+///
+/// # Arguments
+///
+/// * `bytes` - The binary dump
+///
+/// # Errors
+///
+/// * Returns an error of kind `InvalidData` if the data is not a binary dump of a
+///   supported version, or is truncated or corrupted
+///
+/// # Examples
+///
+/// ```rust
+/// use altius_benchtools::profiler;
+///
+/// let dump = serde_json::json!([[{ "type": "other", "name": "task", "start": 0 }]]);
+/// let decoded = profiler::from_binary(&profiler::to_binary(&dump)).unwrap();
+/// let json = serde_json::to_string_pretty(&decoded).unwrap();
+/// ```
+pub fn from_binary(bytes: &[u8]) -> Result<Value, Error> {
+    let mut decoder = Decoder {
+        bytes,
+        position: 0,
+        strings: vec![],
+    };
+    if decoder.take(MAGIC.len()).ok() != Some(MAGIC.as_slice()) {
+        return Err(invalid("missing magic bytes"));
+    }
+    let version = decoder.read_u8()?;
+    if version != VERSION {
+        return Err(invalid(&format!("unsupported version {}", version)));
+    }
+
+    let string_count = decoder.read_len()?;
+    let mut strings = Vec::with_capacity(string_count);
+    for _ in 0..string_count {
+        let len = decoder.read_len()?;
+        let string = std::str::from_utf8(decoder.take(len)?)
+            .map_err(|_| invalid("string is not valid UTF-8"))?;
+        strings.push(string.to_string());
+    }
+    decoder.strings = strings;
+
+    let thread_count = decoder.read_len()?;
+    let mut threads = Vec::with_capacity(thread_count + 1);
+    for _ in 0..thread_count {
+        threads.push(Value::Array(decoder.decode_events()?));
+    }
+    let samples = decoder.decode_events()?;
+    if !samples.is_empty() {
+        threads.push(json!({ COUNTERS_KEY: samples }));
+    }
+    if decoder.position != bytes.len() {
+        return Err(invalid("trailing data"));
+    }
+    Ok(Value::Array(threads))
+}
+
+/// Dumps the profiler data to a file in the compact binary format.
+///
+/// The file holds the same data as [`dump_json()`](super::dump_json) and can be read back
+/// with [`load_binary()`].
+///
+/// # Arguments
+///
+/// * `output_path` - The path where the binary file should be written
+///
+/// # Panics
+///
+/// * Panics if the file cannot be created or written to
+///
+/// # Examples
+///
+/// ```rust
+/// use altius_benchtools::profiler;
+///
+/// // After some profiling...
+/// profiler::dump_binary("profile_results.bin");
+/// ```
+pub fn dump_binary(output_path: &str) {
+    fs::write(output_path, to_binary(&dump_value())).unwrap();
+}
+
+/// Reads a binary dump file written by [`dump_binary()`].
+///
+/// # Arguments
+///
+/// * `path` - The path of the binary file
+///
+/// # Errors
+///
+/// * Returns an error if the file cannot be read or is not a valid binary dump
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use altius_benchtools::profiler;
+///
+/// // Convert a binary dump to JSON
+/// let dump = profiler::load_binary("profile_results.bin").unwrap();
+/// std::fs::write("profile_results.json", serde_json::to_string_pretty(&dump).unwrap()).unwrap();
+/// ```
+pub fn load_binary(path: &str) -> Result<Value, Error> {
+    from_binary(&fs::read(path)?)
+}
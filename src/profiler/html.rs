@@ -0,0 +1,77 @@
+//! Self-contained HTML report of profiling data.
+//!
+//! The report is a single static HTML file (template in `report.html`) embedding the dump
+//! and its summary as JSON, with an interactive per-thread timeline, a transaction status
+//! breakdown and a sortable summary table. It needs no network access or other tooling to
+//! be viewed.
+
+use super::{dump_value, summarize};
+use serde_json::{json, Value};
+use std::{fs::File, io::Write};
+
+const TEMPLATE: &str = include_str!("report.html");
+
+/// Renders a dump produced by [`dump()`](super::dump) as a self-contained HTML report.
+///
+/// # Arguments
+///
+/// * `dump` - The parsed output of [`dump()`](super::dump)
+/// * `title` - The title shown at the top of the report
+///
+/// # Returns
+///
+/// * `String` - The complete HTML document
+///
+/// # Examples
+///
+/// ```rust
+/// use altius_benchtools::profiler;
+///
+/// let dump = serde_json::from_str(&profiler::dump()).unwrap();
+/// let html = profiler::to_html(&dump, "Nightly benchmark");
+/// assert!(html.starts_with("<!DOCTYPE html>"));
+/// ```
+pub fn to_html(dump: &Value, title: &str) -> String {
+    let data = json!({
+        "dump": dump,
+        "summary": summarize(dump),
+    });
+    // `</script>` inside the embedded JSON would end the data block early
+    let data = data.to_string().replace("</", "<\\/");
+    let title = title
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('{', "&#123;");
+    TEMPLATE
+        .replace("{{TITLE}}", &title)
+        .replacen("{{DATA}}", &data, 1)
+}
+
+/// Dumps the profiler data to a self-contained HTML report at the specified path.
+///
+/// The report shows an interactive timeline with one lane per thread (scroll to zoom,
+/// drag to pan, hover a task for its notes), counter tracks, the transaction status
+/// breakdown and the statistical summary of [`summary()`](super::summary).
+///
+/// # Arguments
+///
+/// * `output_path` - The path where the HTML file should be written
+///
+/// # Panics
+///
+/// * Panics if the file cannot be created or written to
+///
+/// # Examples
+///
+/// ```rust
+/// use altius_benchtools::profiler;
+///
+/// // After some profiling...
+/// profiler::dump_html("profile_results.html");
+/// ```
+pub fn dump_html(output_path: &str) {
+    let html = to_html(&dump_value(), "Profiler Report");
+    let mut file = File::create(output_path).unwrap();
+    file.write_all(html.as_bytes()).unwrap();
+}
@@ -0,0 +1,296 @@
+//! Configurable dump output.
+//!
+//! [`DumpOptions`] selects which events of a dump are emitted and how the JSON is
+//! formatted. It applies uniformly to [`dump_with()`], [`dump_json_with()`] and
+//! [`dump_zip_with()`]; the plain [`dump()`](super::dump) family uses the defaults, which
+//! emit everything pretty-printed.
+
+use super::{dump_value, validate, write_zip, Profiler, COUNTERS_KEY};
+use serde_json::Value;
+use std::{fs::File, io::Write};
+
+/// Options controlling the output of [`dump_with()`], [`dump_json_with()`] and
+/// [`dump_zip_with()`].
+///
+/// The default options emit every event, with its notes, as pretty-printed JSON. Filters
+/// combine: an event is emitted only if it passes all of them.
+///
+/// # Examples
+///
+/// ```rust
+/// use altius_benchtools::profiler::{self, DumpOptions};
+///
+/// let options = DumpOptions::new()
+///     .pretty(false)
+///     .types(["transaction", "commit"])
+///     .window(0, 5_000_000_000)
+///     .details(false)
+///     .open_tasks(false);
+/// profiler::dump_json_with("profile_results.json", &options);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DumpOptions {
+    pretty: bool,
+    names: Option<Vec<String>>,
+    types: Option<Vec<String>>,
+    threads: Option<Vec<usize>>,
+    window: Option<(u64, u64)>,
+    details: bool,
+    open_tasks: bool,
+}
+
+impl Default for DumpOptions {
+    fn default() -> Self {
+        Self {
+            pretty: true,
+            names: None,
+            types: None,
+            threads: None,
+            window: None,
+            details: true,
+            open_tasks: true,
+        }
+    }
+}
+
+impl DumpOptions {
+    /// Creates the default options: everything emitted, pretty-printed.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pretty-prints the JSON (`true`, the default) or emits it compact.
+    pub fn pretty(mut self, pretty: bool) -> Self {
+        self.pretty = pretty;
+        self
+    }
+
+    /// Only emits tasks with one of the given names.
+    ///
+    /// Transactions and commits are matched by their `"tx"`, and instances of
+    /// [`start_multi()`](super::start_multi) tasks also match by their base name, without
+    /// the `-[<index>]` suffix. Counter samples are matched by counter name.
+    pub fn names<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.names = Some(names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Only emits events of one of the given types, e.g. `"transaction"`, `"commit"`,
+    /// `"other"` or `"counter"`.
+    pub fn types<I, S>(mut self, types: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.types = Some(types.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Only emits the events of the threads with the given indices in the dump.
+    ///
+    /// Other threads are emitted as empty arrays, so that thread indices stay the same
+    /// as in an unfiltered dump. Counter samples are not affected.
+    pub fn threads<I>(mut self, threads: I) -> Self
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        self.threads = Some(threads.into_iter().collect());
+        self
+    }
+
+    /// Only emits events overlapping the time window `[start, end)`, in nanoseconds since
+    /// the profiler genesis. Events are kept whole, not clipped to the window.
+    pub fn window(mut self, start: u64, end: u64) -> Self {
+        self.window = Some((start, end));
+        self
+    }
+
+    /// Emits the notes of every event under `"detail"` (`true`, the default), or drops
+    /// the `"detail"` field altogether.
+    pub fn details(mut self, details: bool) -> Self {
+        self.details = details;
+        self
+    }
+
+    /// Emits open (un-ended) tasks with `"end": null` (`true`, the default), or omits them.
+    pub fn open_tasks(mut self, open_tasks: bool) -> Self {
+        self.open_tasks = open_tasks;
+        self
+    }
+
+    /// Returns whether an event of the thread with the given index passes the filters
+    fn keeps(&self, thread_idx: usize, event: &Value) -> bool {
+        let is_counter = event["type"] == "counter";
+        if let Some(threads) = &self.threads {
+            if !is_counter && !threads.contains(&thread_idx) {
+                return false;
+            }
+        }
+        if let Some(types) = &self.types {
+            if !types.iter().any(|t| event["type"] == t.as_str()) {
+                return false;
+            }
+        }
+        if let Some(names) = &self.names {
+            let name = match event.get("name") {
+                Some(name) => name.as_str(),
+                None => event["tx"].as_str(),
+            }
+            .unwrap_or_default();
+            let base = match name.rfind("-[") {
+                Some(index) if name.ends_with(']') => &name[..index],
+                _ => name,
+            };
+            if !names.iter().any(|n| n == name || n == base) {
+                return false;
+            }
+        }
+        if !self.open_tasks && !is_counter && event["end"].is_null() {
+            return false;
+        }
+        if let Some((window_start, window_end)) = self.window {
+            let start = event["start"].as_u64().unwrap_or(0);
+            let end = start + event["runtime"].as_u64().unwrap_or(0);
+            let overlaps = if start == end {
+                window_start <= start && start < window_end
+            } else {
+                start < window_end && end > window_start
+            };
+            if !overlaps {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Keeps the events of the thread with the given index that pass the filters
+    fn filter(&self, thread_idx: usize, events: Vec<Value>) -> Vec<Value> {
+        events
+            .into_iter()
+            .filter(|event| self.keeps(thread_idx, event))
+            .map(|mut event| {
+                if !self.details {
+                    if let Some(map) = event.as_object_mut() {
+                        map.remove("detail");
+                    }
+                }
+                event
+            })
+            .collect()
+    }
+
+    /// Applies the filters to a dump produced by [`dump_value()`]
+    fn apply(&self, dump: Value) -> Value {
+        let Value::Array(threads) = dump else {
+            return dump;
+        };
+        let threads = threads
+            .into_iter()
+            .enumerate()
+            .map(|(thread_idx, entry)| match entry {
+                Value::Array(events) => Value::Array(self.filter(thread_idx, events)),
+                // the counter samples, after the per-thread arrays
+                Value::Object(mut counters) => {
+                    if let Some(Value::Array(samples)) = counters.remove(COUNTERS_KEY) {
+                        let samples = self.filter(thread_idx, samples);
+                        counters.insert(COUNTERS_KEY.to_string(), Value::Array(samples));
+                    }
+                    Value::Object(counters)
+                }
+                other => other,
+            })
+            .collect();
+        Value::Array(threads)
+    }
+
+    /// Serializes a dump according to the options
+    fn render(&self, dump: Value) -> String {
+        let dump = self.apply(dump);
+        if self.pretty {
+            serde_json::to_string_pretty(&dump).unwrap()
+        } else {
+            serde_json::to_string(&dump).unwrap()
+        }
+    }
+}
+
+/// Returns the current profiling data as a JSON string, as configured by `options`.
+///
+/// With the default options, this is the same as [`dump()`](super::dump).
+///
+/// # Arguments
+///
+/// * `options` - The filters and formatting of the output
+///
+/// # Returns
+///
+/// * `String` - The JSON string containing the selected profiling data
+///
+/// # Examples
+///
+/// ```rust
+/// use altius_benchtools::profiler::{self, DumpOptions};
+///
+/// let compact = profiler::dump_with(&DumpOptions::new().pretty(false));
+/// assert!(!compact.contains('\n'));
+/// ```
+pub fn dump_with(options: &DumpOptions) -> String {
+    let dump = dump_value();
+    if Profiler::global().lock().unwrap().warn_on_dump {
+        validate::warn(&validate(&dump));
+    }
+    options.render(dump)
+}
+
+/// Dumps the profiler data to a JSON file at the specified path, as configured by `options`.
+///
+/// # Arguments
+///
+/// * `output_path` - The path where the JSON file should be written
+/// * `options` - The filters and formatting of the output
+///
+/// # Panics
+///
+/// * Panics if the file cannot be created or written to
+///
+/// # Examples
+///
+/// ```rust
+/// use altius_benchtools::profiler::{self, DumpOptions};
+///
+/// // After some profiling...
+/// profiler::dump_json_with("profile_results.json", &DumpOptions::new().pretty(false));
+/// ```
+pub fn dump_json_with(output_path: &str, options: &DumpOptions) {
+    let result_json = dump_with(options);
+    let mut file = File::create(output_path).unwrap();
+    file.write_all(result_json.as_bytes()).unwrap();
+}
+
+/// Dumps the profiler data to a ZIP file containing a JSON file, as configured by `options`.
+///
+/// # Arguments
+///
+/// * `output_name` - The base name for the output files (without extension)
+/// * `options` - The filters and formatting of the output
+///
+/// # Panics
+///
+/// * Panics if the ZIP file cannot be created or written to
+///
+/// # Examples
+///
+/// ```rust
+/// use altius_benchtools::profiler::{self, DumpOptions};
+///
+/// // After some profiling...
+/// profiler::dump_zip_with("profile_results", &DumpOptions::new().details(false));
+/// // Creates profile_results.zip containing profile_results.json
+/// ```
+pub fn dump_zip_with(output_name: &str, options: &DumpOptions) {
+    write_zip(output_name, output_name, &dump_with(options)).unwrap();
+}
@@ -0,0 +1,261 @@
+//! OpenTelemetry trace export of profiling data.
+//!
+//! Every task of a dump becomes one OTLP span: the notes of the task become span
+//! attributes, and every profiled thread becomes its own resource, identified by the
+//! `altius.thread.index` resource attribute. This is the index of the thread in the dump,
+//! not an OS thread id, and the order of threads may differ between two dumps of the same
+//! run. Within a thread, a span is parented to the innermost span whose time range
+//! contains it. All spans of a dump share one trace. Counter samples are not exported.
+//!
+//! Spans are encoded in the OTLP/JSON format (`ExportTraceServiceRequest`), which can be
+//! written to a file or posted to the OTLP/HTTP endpoint of a collector.
+
+use super::{dump_value, get_genesis_unix_nano};
+use serde_json::{json, Value};
+use std::{
+    fs::File,
+    io::{Error, ErrorKind, Read, Write},
+    net::TcpStream,
+};
+
+/// Converts a JSON value into an OTLP `AnyValue`
+fn any_value(value: &Value) -> Value {
+    match value {
+        Value::String(string) => json!({ "stringValue": string }),
+        Value::Bool(boolean) => json!({ "boolValue": boolean }),
+        Value::Number(number) if number.is_i64() || number.is_u64() => {
+            json!({ "intValue": number.to_string() })
+        }
+        Value::Number(number) => json!({ "doubleValue": number.as_f64() }),
+        other => json!({ "stringValue": other.to_string() }),
+    }
+}
+
+fn attribute(key: &str, value: &Value) -> Value {
+    json!({ "key": key, "value": any_value(value) })
+}
+
+/// Converts the events of one thread of a dump into OTLP spans
+fn thread_spans(events: &[Value], trace_id: &str, genesis_unix_nano: u128) -> Vec<Value> {
+    let mut events: Vec<&Value> = events.iter().collect();
+    let bounds = |event: &Value| {
+        let start = event["start"].as_u64().unwrap_or(0);
+        (start, start + event["runtime"].as_u64().unwrap_or(0))
+    };
+    // outer spans first, so that parents are always visited before their children
+    events.sort_by_key(|event| {
+        let (start, end) = bounds(event);
+        (start, std::cmp::Reverse(end))
+    });
+
+    let mut spans = vec![];
+    let mut stack: Vec<(u64, String)> = vec![]; // end time & span id of enclosing spans
+    for event in events {
+        let (start, end) = bounds(event);
+        while stack
+            .last()
+            .is_some_and(|(parent_end, _)| *parent_end < end)
+        {
+            stack.pop();
+        }
+        let span_id = format!("{:016x}", rand::random::<u64>());
+
+        let event_type = event["type"].as_str().unwrap_or("other");
+        let name = match event_type {
+            "transaction" | "commit" => format!(
+                "{} {}",
+                event_type,
+                event["tx"].as_str().unwrap_or("unknown")
+            ),
+            _ => event["name"].as_str().unwrap_or("unknown").to_string(),
+        };
+        let mut attributes = vec![attribute("profiler.type", &json!(event_type))];
+        for key in ["tx", "status", "cpu_runtime"] {
+            if !event[key].is_null() {
+                attributes.push(attribute(&format!("profiler.{}", key), &event[key]));
+            }
+        }
+        for (key, value) in event["perf"].as_object().into_iter().flatten() {
+            attributes.push(attribute(&format!("profiler.perf.{}", key), value));
+        }
+        if event["end"].is_null() {
+            attributes.push(attribute("profiler.open", &json!(true)));
+        }
+        for (key, value) in event["detail"].as_object().into_iter().flatten() {
+            attributes.push(attribute(key, value));
+        }
+
+        let status = match event["status"].as_str() {
+            Some("success") => json!({ "code": 1 }),
+            Some("unknown") | None => json!({ "code": 0 }),
+            Some(status) => json!({ "code": 2, "message": status }),
+        };
+
+        spans.push(json!({
+            "traceId": trace_id,
+            "spanId": span_id,
+            "parentSpanId": stack.last().map(|(_, id)| id.as_str()).unwrap_or_default(),
+            "name": name,
+            "kind": 1,
+            "startTimeUnixNano": (genesis_unix_nano + start as u128).to_string(),
+            "endTimeUnixNano": (genesis_unix_nano + end as u128).to_string(),
+            "attributes": attributes,
+            "status": status,
+        }));
+        stack.push((end, span_id));
+    }
+    spans
+}
+
+/// Converts a dump produced by [`dump()`](super::dump) into an OTLP/JSON trace export request.
+///
+/// # Arguments
+///
+/// * `dump` - The parsed output of [`dump()`](super::dump)
+/// * `service_name` - The `service.name` resource attribute of all spans
+/// * `genesis_unix_nano` - The wall-clock time the dump's timestamps are relative to, in
+///   nanoseconds since the Unix epoch (see [`get_genesis_unix_nano()`](super::get_genesis_unix_nano))
+///
+/// # Returns
+///
+/// * `Value` - An `ExportTraceServiceRequest` in the OTLP/JSON encoding
+///
+/// # Examples
+///
+/// ```rust
+/// use altius_benchtools::profiler;
+///
+/// profiler::start("block");
+/// profiler::end("block");
+///
+/// let dump = serde_json::from_str(&profiler::dump()).unwrap();
+/// let request = profiler::to_otlp(&dump, "my-node", profiler::get_genesis_unix_nano());
+/// assert!(request["resourceSpans"].is_array());
+/// ```
+pub fn to_otlp(dump: &Value, service_name: &str, genesis_unix_nano: u128) -> Value {
+    let trace_id = format!("{:032x}", rand::random::<u128>());
+    let resource_spans: Vec<Value> = dump
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_array)
+        .enumerate()
+        .filter_map(|(thread_idx, events)| {
+            let spans = thread_spans(events, &trace_id, genesis_unix_nano);
+            if spans.is_empty() {
+                return None;
+            }
+            Some(json!({
+                "resource": {
+                    "attributes": [
+                        attribute("service.name", &json!(service_name)),
+                        attribute("altius.thread.index", &json!(thread_idx)),
+                    ]
+                },
+                "scopeSpans": [{
+                    "scope": {
+                        "name": env!("CARGO_PKG_NAME"),
+                        "version": env!("CARGO_PKG_VERSION"),
+                    },
+                    "spans": spans,
+                }]
+            }))
+        })
+        .collect();
+    json!({ "resourceSpans": resource_spans })
+}
+
+/// Dumps the profiler data as OTLP/JSON spans to a file at the specified path.
+///
+/// The file contains one `ExportTraceServiceRequest` (see [`to_otlp()`]) and can be
+/// replayed to a collector or loaded by any tool that reads the OTLP/JSON file format.
+///
+/// # Arguments
+///
+/// * `output_path` - The path where the OTLP/JSON file should be written
+/// * `service_name` - The `service.name` resource attribute of all spans
+///
+/// # Panics
+///
+/// * Panics if the file cannot be created or written to
+///
+/// # Examples
+///
+/// ```rust
+/// use altius_benchtools::profiler;
+///
+/// // After some profiling...
+/// profiler::dump_otlp("profile_results.otlp.json", "my-node");
+/// ```
+pub fn dump_otlp(output_path: &str, service_name: &str) {
+    let request = to_otlp(&dump_value(), service_name, get_genesis_unix_nano());
+    let mut file = File::create(output_path).unwrap();
+    file.write_all(request.to_string().as_bytes()).unwrap();
+}
+
+/// Pushes the profiler data as OTLP spans to a collector.
+///
+/// The spans are posted as OTLP/JSON to the given OTLP/HTTP traces endpoint, typically
+/// `http://localhost:4318/v1/traces`. Only plain `http://` endpoints are supported.
+///
+/// # Arguments
+///
+/// * `endpoint` - The URL of the collector's traces endpoint
+/// * `service_name` - The `service.name` resource attribute of all spans
+///
+/// # Errors
+///
+/// * Returns an error if the endpoint is not a valid `http://` URL, cannot be reached,
+///   or does not answer with a 2xx status
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use altius_benchtools::profiler;
+///
+/// // After some profiling...
+/// profiler::export_otlp("http://localhost:4318/v1/traces", "my-node").unwrap();
+/// ```
+pub fn export_otlp(endpoint: &str, service_name: &str) -> Result<(), Error> {
+    let invalid = || {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("invalid OTLP endpoint: {}", endpoint),
+        )
+    };
+    let location = endpoint.strip_prefix("http://").ok_or_else(invalid)?;
+    let (host, path) = match location.find('/') {
+        Some(index) => location.split_at(index),
+        None => (location, "/v1/traces"),
+    };
+    if host.is_empty() {
+        return Err(invalid());
+    }
+    let host_port = if host.contains(':') {
+        host.to_string()
+    } else {
+        format!("{}:80", host)
+    };
+
+    let body = to_otlp(&dump_value(), service_name, get_genesis_unix_nano()).to_string();
+    let mut stream = TcpStream::connect(host_port)?;
+    write!(
+        stream,
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        path,
+        host,
+        body.len()
+    )?;
+    stream.write_all(body.as_bytes())?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    let status_line = response.lines().next().unwrap_or_default();
+    match status_line.split_whitespace().nth(1) {
+        Some(code) if code.starts_with('2') => Ok(()),
+        _ => Err(Error::other(format!(
+            "OTLP collector rejected the export: {}",
+            status_line
+        ))),
+    }
+}
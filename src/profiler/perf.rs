@@ -0,0 +1,161 @@
+//! Hardware performance counters per task (Linux only, `perf` feature).
+//!
+//! Every thread lazily opens one `perf_event_open(2)` counter group for itself the first
+//! time a task is started with perf counters enabled. The group is read at [`super::start()`]
+//! and [`super::end()`], and the deltas are emitted under `"perf"` in the dump.
+//!
+//! If perf events are unavailable (no PMU in a VM, `perf_event_paranoid` too strict, seccomp,
+//! ...), tasks are simply recorded without counters. Counters the hardware does not support
+//! are left out individually.
+
+use super::Profiler;
+use perf_event_open_sys::{bindings, perf_event_open};
+use std::{cell::RefCell, os::raw::c_int};
+
+/// Hardware events recorded for every task, with the names they are emitted under
+const EVENTS: [(&str, u32); 4] = [
+    (
+        "instructions",
+        bindings::perf_hw_id_PERF_COUNT_HW_INSTRUCTIONS,
+    ),
+    ("cycles", bindings::perf_hw_id_PERF_COUNT_HW_CPU_CYCLES),
+    (
+        "cache_misses",
+        bindings::perf_hw_id_PERF_COUNT_HW_CACHE_MISSES,
+    ),
+    (
+        "branch_misses",
+        bindings::perf_hw_id_PERF_COUNT_HW_BRANCH_MISSES,
+    ),
+];
+
+/// Counter values read at one point in time, by event name
+pub(super) type PerfValues = Vec<(&'static str, u64)>;
+
+/// The counter group opened for one thread
+struct CounterGroup {
+    fds: Vec<c_int>, // the first one is the group leader
+    names: Vec<&'static str>,
+}
+
+impl CounterGroup {
+    /// Opens the counters of the current thread, or returns `None` if not even the group
+    /// leader could be opened
+    fn open() -> Option<Self> {
+        let mut group = CounterGroup {
+            fds: vec![],
+            names: vec![],
+        };
+        for (name, config) in EVENTS {
+            let mut attr = bindings::perf_event_attr {
+                type_: bindings::perf_type_id_PERF_TYPE_HARDWARE,
+                size: std::mem::size_of::<bindings::perf_event_attr>() as u32,
+                config: config as u64,
+                read_format: bindings::perf_event_read_format_PERF_FORMAT_GROUP as u64,
+                ..Default::default()
+            };
+            attr.set_exclude_kernel(1);
+            attr.set_exclude_hv(1);
+            let group_fd = group.fds.first().copied().unwrap_or(-1);
+            let fd = unsafe {
+                perf_event_open(
+                    &mut attr,
+                    0,
+                    -1,
+                    group_fd,
+                    bindings::PERF_FLAG_FD_CLOEXEC as _,
+                )
+            };
+            if fd >= 0 {
+                group.fds.push(fd);
+                group.names.push(name);
+            } else if group.fds.is_empty() {
+                return None;
+            }
+        }
+        Some(group)
+    }
+
+    /// Reads all counters of the group at once
+    fn read(&self) -> Option<PerfValues> {
+        let mut buffer = vec![0u64; 1 + self.fds.len()];
+        let size = std::mem::size_of_val(buffer.as_slice());
+        let read = unsafe { libc::read(self.fds[0], buffer.as_mut_ptr() as *mut _, size) };
+        if read != size as isize || buffer[0] as usize != self.fds.len() {
+            return None;
+        }
+        Some(
+            self.names
+                .iter()
+                .copied()
+                .zip(buffer[1..].iter().copied())
+                .collect(),
+        )
+    }
+}
+
+impl Drop for CounterGroup {
+    fn drop(&mut self) {
+        for fd in self.fds.iter().rev() {
+            unsafe { libc::close(*fd) };
+        }
+    }
+}
+
+thread_local! {
+    /// `None` until the first read, then `Some(None)` if counters are unavailable
+    static COUNTER_GROUP: RefCell<Option<Option<CounterGroup>>> = const { RefCell::new(None) };
+}
+
+/// Reads the hardware counters of the current thread, opening them on first use
+pub(super) fn read_counters() -> Option<PerfValues> {
+    COUNTER_GROUP.with(|group| {
+        group
+            .borrow_mut()
+            .get_or_insert_with(CounterGroup::open)
+            .as_ref()
+            .and_then(CounterGroup::read)
+    })
+}
+
+/// Computes the per-event difference between two reads of the same thread's counters
+pub(super) fn delta(
+    start: &PerfValues,
+    end: &PerfValues,
+) -> serde_json::Map<String, serde_json::Value> {
+    start
+        .iter()
+        .zip(end)
+        .map(|((name, start), (_, end))| (name.to_string(), end.saturating_sub(*start).into()))
+        .collect()
+}
+
+/// Enables or disables recording hardware performance counters per task.
+///
+/// When enabled, [`start()`](super::start) and [`end()`](super::end) read the instructions
+/// retired, CPU cycles, cache misses and branch misses of the current thread, and
+/// [`dump()`](super::dump) emits their deltas as a `"perf"` object on every task started
+/// while recording was on (`"perf": null` for open tasks). Counters only cover user space
+/// execution of the thread that runs the task.
+///
+/// If perf events are unavailable on this machine, tasks are recorded without the `"perf"`
+/// field instead of failing. Recording is disabled by default.
+///
+/// # Arguments
+///
+/// * `enabled` - Whether tasks started from now on should record hardware counters
+///
+/// # Examples
+///
+/// ```rust
+/// use altius_benchtools::profiler;
+///
+/// profiler::enable_perf_counters(true);
+/// profiler::start("interpreter_loop");
+/// // ... execute bytecode ...
+/// profiler::end("interpreter_loop");
+/// ```
+pub fn enable_perf_counters(enabled: bool) {
+    let mut profiler = Profiler::global().lock().unwrap();
+    profiler.track_perf_counters = enabled;
+}
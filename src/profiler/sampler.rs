@@ -0,0 +1,188 @@
+//! Background sampler of process resource metrics (Linux only).
+//!
+//! The sampler periodically reads `/proc/self/*` and records the results as profiler
+//! counters (see [`super::counter()`]), so the machine's state can be lined up with the
+//! task timeline in the dump. Metrics that cannot be read (e.g. `/proc/self/io` in some
+//! containers) are skipped silently.
+//!
+//! Recorded counter tracks:
+//!
+//! - `rss_bytes` - resident set size of the process
+//! - `ctx_switches_voluntary` / `ctx_switches_involuntary` - cumulative context switches
+//! - `page_faults_minor` / `page_faults_major` - cumulative page faults
+//! - `io_read_bytes` / `io_write_bytes` - cumulative bytes fetched from / sent to storage
+//! - `io_rchar` / `io_wchar` - cumulative bytes passed to `read`/`write`-like syscalls
+//! - `thread_cpu_ns[<name>:<tid>]` - cumulative CPU time (user + system) of each OS thread
+
+use super::counter;
+use std::{
+    fs,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+/// Handle of a running background sampler.
+///
+/// The sampler keeps recording until [`Sampler::stop()`] is called or the handle is
+/// dropped, so the handle must be kept alive while sampling.
+#[must_use = "sampling stops when the Sampler is dropped"]
+pub struct Sampler {
+    running: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Sampler {
+    /// Stops the sampler and waits for its thread to exit.
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            handle.thread().unpark();
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for Sampler {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Starts a background thread sampling process resource metrics every `interval`.
+///
+/// One sample of every metric is taken immediately, then once per interval until the
+/// returned [`Sampler`] is stopped or dropped.
+///
+/// # Arguments
+///
+/// * `interval` - Time between two consecutive samples
+///
+/// # Examples
+///
+/// ```rust
+/// use altius_benchtools::profiler;
+/// use std::time::Duration;
+///
+/// let sampler = profiler::start_sampler(Duration::from_millis(10));
+/// profiler::start("heavy_task");
+/// // ... perform work ...
+/// profiler::end("heavy_task");
+/// sampler.stop();
+///
+/// profiler::dump_json("profile_with_counters.json");
+/// ```
+pub fn start_sampler(interval: Duration) -> Sampler {
+    let running = Arc::new(AtomicBool::new(true));
+    let flag = running.clone();
+    let handle = thread::Builder::new()
+        .name("profiler-sampler".to_string())
+        .spawn(move || {
+            let clock_ticks = clock_ticks_per_second();
+            while flag.load(Ordering::SeqCst) {
+                sample(clock_ticks);
+                thread::park_timeout(interval);
+            }
+        })
+        .unwrap();
+    Sampler {
+        running,
+        handle: Some(handle),
+    }
+}
+
+fn clock_ticks_per_second() -> u64 {
+    match unsafe { libc::sysconf(libc::_SC_CLK_TCK) } {
+        ticks if ticks > 0 => ticks as u64,
+        _ => 100,
+    }
+}
+
+/// Takes one sample of every metric and records it as profiler counters
+fn sample(clock_ticks: u64) {
+    if let Ok(status) = fs::read_to_string("/proc/self/status") {
+        for (key, value) in parse_key_values(&status) {
+            match key {
+                "VmRSS" => counter("rss_bytes", value * 1024),
+                "voluntary_ctxt_switches" => counter("ctx_switches_voluntary", value),
+                "nonvoluntary_ctxt_switches" => counter("ctx_switches_involuntary", value),
+                _ => (),
+            }
+        }
+    }
+
+    if let Some(fields) = fs::read_to_string("/proc/self/stat")
+        .ok()
+        .and_then(|stat| parse_stat(&stat))
+    {
+        counter("page_faults_minor", fields.minflt);
+        counter("page_faults_major", fields.majflt);
+    }
+
+    if let Ok(io) = fs::read_to_string("/proc/self/io") {
+        for (key, value) in parse_key_values(&io) {
+            match key {
+                "read_bytes" => counter("io_read_bytes", value),
+                "write_bytes" => counter("io_write_bytes", value),
+                "rchar" => counter("io_rchar", value),
+                "wchar" => counter("io_wchar", value),
+                _ => (),
+            }
+        }
+    }
+
+    if let Ok(tasks) = fs::read_dir("/proc/self/task") {
+        for task in tasks.flatten() {
+            let tid = task.file_name().to_string_lossy().to_string();
+            if let Some(fields) = fs::read_to_string(task.path().join("stat"))
+                .ok()
+                .and_then(|stat| parse_stat(&stat))
+            {
+                let cpu_ns = (fields.utime + fields.stime) * 1_000_000_000 / clock_ticks;
+                counter(&format!("thread_cpu_ns[{}:{}]", fields.comm, tid), cpu_ns);
+            }
+        }
+    }
+}
+
+/// Parses `Key: value [unit]` lines as found in `/proc/self/status` and `/proc/self/io`
+fn parse_key_values(content: &str) -> impl Iterator<Item = (&str, u64)> {
+    content.lines().filter_map(|line| {
+        let (key, rest) = line.split_once(':')?;
+        let value = rest.split_whitespace().next()?.parse().ok()?;
+        Some((key.trim(), value))
+    })
+}
+
+struct StatFields {
+    comm: String,
+    minflt: u64,
+    majflt: u64,
+    utime: u64,
+    stime: u64,
+}
+
+/// Parses the fields of interest out of a `/proc/<pid>/stat` line
+fn parse_stat(stat: &str) -> Option<StatFields> {
+    // The command name is wrapped in parentheses and may itself contain spaces or
+    // parentheses, so the remaining fields start after the last ')'.
+    let comm_start = stat.find('(')?;
+    let comm_end = stat.rfind(')')?;
+    let fields: Vec<&str> = stat[comm_end + 1..].split_whitespace().collect();
+    // `fields[0]` is field 3 (state) of proc(5)
+    let field = |index: usize| fields.get(index - 3)?.parse::<u64>().ok();
+    Some(StatFields {
+        comm: stat[comm_start + 1..comm_end].to_string(),
+        minflt: field(10)?,
+        majflt: field(12)?,
+        utime: field(14)?,
+        stime: field(15)?,
+    })
+}
@@ -0,0 +1,294 @@
+//! Embedded HTTP server exposing in-progress profiling data (`http` feature).
+//!
+//! Routes:
+//!
+//! - `GET /dump` - the current [`super::dump()`]
+//! - `GET /summary` - the current [`super::summary()`]
+//! - `GET /metrics` - task runtimes, transaction statuses and counters in the Prometheus
+//!   text exposition format
+//! - `POST /clear` - calls [`super::clear()`]
+//! - `POST /dump_json?path=<file>` - writes the dump to `<file>` like [`super::dump_json()`]
+//! - `POST /dump_zip?name=<name>` - writes the dump to `<name>.zip` like [`super::dump_zip()`]
+//!
+//! The server only listens on loopback addresses, and the dump routes only take bare file
+//! names, written to the dump directory given to [`start_server()`].
+
+use super::{clear, dump, dump_value, summarize, write_zip};
+use serde_json::Value;
+use std::{
+    fmt::Write as _,
+    fs,
+    io::{Error, ErrorKind},
+    net::{SocketAddr, ToSocketAddrs},
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+    thread::{self, JoinHandle},
+};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// Handle of a running profiler HTTP server.
+///
+/// The server keeps serving requests until [`HttpServer::stop()`] is called or the handle
+/// is dropped.
+pub struct HttpServer {
+    server: Arc<Server>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl HttpServer {
+    /// Returns the address the server is listening on.
+    pub fn addr(&self) -> SocketAddr {
+        self.server.server_addr().to_ip().unwrap()
+    }
+
+    /// Stops the server and waits for its thread to exit.
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.server.unblock();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for HttpServer {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Starts an HTTP server exposing the profiling data of this process.
+///
+/// The server runs on a background thread, so a long benchmark can be inspected without
+/// stopping it. It has no authentication, so it only binds loopback addresses such as
+/// `127.0.0.1:9898`. Use port `0` to let the OS pick a free port, then read it back with
+/// [`HttpServer::addr()`].
+///
+/// # Arguments
+///
+/// * `addr` - The loopback socket address to listen on
+/// * `dump_dir` - The directory the dump routes write to, created if missing
+///
+/// # Errors
+///
+/// * Returns an error of kind `InvalidInput` if `addr` resolves to a non-loopback address
+/// * Returns an error if the dump directory cannot be created or the address cannot be bound
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use altius_benchtools::profiler;
+///
+/// let server = profiler::start_server("127.0.0.1:9898", "profiles").unwrap();
+/// // ... run the benchmark, meanwhile:
+/// // $ curl localhost:9898/summary
+/// // $ curl localhost:9898/metrics
+/// // $ curl -X POST 'localhost:9898/dump_json?path=partial.json'  # profiles/partial.json
+/// server.stop();
+/// ```
+pub fn start_server(addr: &str, dump_dir: &str) -> Result<HttpServer, Error> {
+    let addrs: Vec<SocketAddr> = addr.to_socket_addrs()?.collect();
+    if addrs.is_empty() || !addrs.iter().all(|addr| addr.ip().is_loopback()) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("{} is not a loopback address", addr),
+        ));
+    }
+    fs::create_dir_all(dump_dir)?;
+    let server = Arc::new(Server::http(addrs.as_slice()).map_err(Error::other)?);
+    let handle = {
+        let server = server.clone();
+        let dump_dir = PathBuf::from(dump_dir);
+        thread::Builder::new()
+            .name("profiler-http".to_string())
+            .spawn(move || {
+                for request in server.incoming_requests() {
+                    handle_request(request, &dump_dir);
+                }
+            })?
+    };
+    Ok(HttpServer {
+        server,
+        handle: Some(handle),
+    })
+}
+
+fn handle_request(request: Request, dump_dir: &Path) {
+    let (path, query) = match request.url().split_once('?') {
+        Some((path, query)) => (path.to_string(), query.to_string()),
+        None => (request.url().to_string(), String::new()),
+    };
+    let (status, content_type, body) = match (request.method(), path.as_str()) {
+        (Method::Get, "/dump") => (200, "application/json", dump()),
+        (Method::Get, "/summary") => (
+            200,
+            "application/json",
+            serde_json::to_string_pretty(&summarize(&dump_value())).unwrap(),
+        ),
+        (Method::Get, "/metrics") => (
+            200,
+            "text/plain; version=0.0.4",
+            metrics(&summarize(&dump_value())),
+        ),
+        (Method::Post, "/clear") => {
+            clear();
+            (200, "text/plain", "cleared\n".to_string())
+        }
+        (Method::Post, "/dump_json") => match dump_path(dump_dir, &query, "path") {
+            Ok(path) => match fs::write(&path, dump()) {
+                Ok(()) => (
+                    200,
+                    "text/plain",
+                    format!("written to {}\n", path.display()),
+                ),
+                Err(e) => (500, "text/plain", format!("{}\n", e)),
+            },
+            Err(message) => (400, "text/plain", message),
+        },
+        (Method::Post, "/dump_zip") => match dump_path(dump_dir, &query, "name") {
+            // the archive holds `<name>.json`, without the dump directory
+            Ok(path) => match write_zip(
+                &path.to_string_lossy(),
+                &path.file_name().unwrap().to_string_lossy(),
+                &dump(),
+            ) {
+                Ok(()) => (
+                    200,
+                    "text/plain",
+                    format!("written to {}.zip\n", path.display()),
+                ),
+                Err(e) => (500, "text/plain", format!("{}\n", e)),
+            },
+            Err(message) => (400, "text/plain", message),
+        },
+        _ => (404, "text/plain", "not found\n".to_string()),
+    };
+    let header = Header::from_bytes("Content-Type", content_type).unwrap();
+    let response = Response::from_string(body)
+        .with_status_code(status)
+        .with_header(header);
+    let _ = request.respond(response);
+}
+
+/// Returns the path in the dump directory named by a query string parameter, which must
+/// be a bare file name
+fn dump_path(dump_dir: &Path, query: &str, key: &str) -> Result<PathBuf, String> {
+    let name = query_param(query, key).ok_or_else(|| format!("missing `{}` parameter\n", key))?;
+    let mut components = Path::new(&name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(file_name)), None) => Ok(dump_dir.join(file_name)),
+        _ => Err(format!("`{}` must be a bare file name\n", key)),
+    }
+}
+
+/// Returns the percent-decoded value of a query string parameter
+fn query_param(query: &str, key: &str) -> Option<String> {
+    let value = query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(name, _)| *name == key)?
+        .1;
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'%' if index + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[index + 1..index + 3]).ok()?;
+                decoded.push(u8::from_str_radix(hex, 16).ok()?);
+                index += 3;
+            }
+            b'+' => {
+                decoded.push(b' ');
+                index += 1;
+            }
+            byte => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+/// Escapes a Prometheus label value
+fn label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Renders a summary (see [`summarize()`]) in the Prometheus text exposition format
+fn metrics(summary: &Value) -> String {
+    let mut page = String::new();
+    let tasks = summary["tasks"].as_array().unwrap();
+
+    page += "# HELP profiler_task_runtime_nanoseconds Runtime of ended profiler tasks.\n";
+    page += "# TYPE profiler_task_runtime_nanoseconds summary\n";
+    for task in tasks {
+        let labels = format!(
+            "type=\"{}\",task=\"{}\"",
+            label(task["type"].as_str().unwrap_or_default()),
+            label(task["name"].as_str().unwrap_or_default())
+        );
+        for (quantile, key) in [("0.5", "p50"), ("0.95", "p95"), ("0.99", "p99")] {
+            if let Some(value) = task[key].as_u64() {
+                let _ = writeln!(
+                    page,
+                    "profiler_task_runtime_nanoseconds{{{},quantile=\"{}\"}} {}",
+                    labels, quantile, value
+                );
+            }
+        }
+        let _ = writeln!(
+            page,
+            "profiler_task_runtime_nanoseconds_sum{{{}}} {}",
+            labels, task["total"]
+        );
+        let _ = writeln!(
+            page,
+            "profiler_task_runtime_nanoseconds_count{{{}}} {}",
+            labels, task["count"]
+        );
+    }
+
+    page += "# HELP profiler_open_tasks Number of profiler tasks started but not ended.\n";
+    page += "# TYPE profiler_open_tasks gauge\n";
+    for task in tasks {
+        let _ = writeln!(
+            page,
+            "profiler_open_tasks{{type=\"{}\",task=\"{}\"}} {}",
+            label(task["type"].as_str().unwrap_or_default()),
+            label(task["name"].as_str().unwrap_or_default()),
+            task["open"]
+        );
+    }
+
+    page += "# HELP profiler_transactions Number of profiled transactions by status.\n";
+    page += "# TYPE profiler_transactions gauge\n";
+    for (status, count) in summary["transactions"].as_object().unwrap() {
+        let _ = writeln!(
+            page,
+            "profiler_transactions{{status=\"{}\"}} {}",
+            label(status),
+            count
+        );
+    }
+
+    page += "# HELP profiler_counter Last sampled value of profiler counters.\n";
+    page += "# TYPE profiler_counter gauge\n";
+    for (name, counter) in summary["counters"].as_object().unwrap() {
+        let _ = writeln!(
+            page,
+            "profiler_counter{{name=\"{}\"}} {}",
+            label(name),
+            counter["last"]
+        );
+    }
+
+    page
+}
@@ -0,0 +1,171 @@
+//! Statistical summary of profiling data.
+//!
+//! The summary aggregates the events of a dump (see [`super::dump()`]) per task: events of
+//! type `"transaction"` and `"commit"` are grouped by their type, all other events by name,
+//! with the `-[<index>]` suffix of [`super::start_multi()`] tasks stripped so that every
+//! instance of a multi task lands in the same group. Runtime statistics only cover ended
+//! events; open events are counted separately.
+
+use super::{dump_counters, dump_value};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+/// Returns the group an event of a dump is summarized under, as `(type, name)`
+pub(super) fn group_of(event: &Value) -> (String, String) {
+    let event_type = event["type"].as_str().unwrap_or("other").to_string();
+    let name = match event_type.as_str() {
+        "transaction" | "commit" => event_type.clone(),
+        _ => {
+            let name = event["name"].as_str().unwrap_or("unknown");
+            match name.rfind("-[") {
+                Some(index) if name.ends_with(']') => name[..index].to_string(),
+                _ => name.to_string(),
+            }
+        }
+    };
+    (event_type, name)
+}
+
+/// Returns the value at the given quantile of a sorted, non-empty slice (nearest rank)
+fn quantile(sorted: &[u64], quantile: f64) -> u64 {
+    let rank = (quantile * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Summarizes a dump produced by [`super::dump()`] (parsed as a JSON value).
+///
+/// This is the building block of [`summary()`], exposed so that dumps read back from disk
+/// can be summarized as well.
+///
+/// # Arguments
+///
+/// * `dump` - The parsed output of [`super::dump()`]
+///
+/// # Returns
+///
+/// * `Value` - The summary, in the format documented on [`summary()`]
+pub fn summarize(dump: &Value) -> Value {
+    let mut tasks: BTreeMap<(String, String), (Vec<u64>, u64)> = BTreeMap::new();
+    let mut statuses: BTreeMap<String, u64> = BTreeMap::new();
+    let mut counters: BTreeMap<String, Vec<u64>> = BTreeMap::new();
+
+    for event in dump
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_array)
+        .flatten()
+    {
+        if event["type"] == "transaction" {
+            *statuses
+                .entry(event["status"].as_str().unwrap_or("unknown").to_string())
+                .or_default() += 1;
+        }
+        let (runtimes, open) = tasks.entry(group_of(event)).or_default();
+        if event["end"].is_null() {
+            *open += 1;
+        } else {
+            runtimes.push(event["runtime"].as_u64().unwrap_or(0));
+        }
+    }
+
+    let mut task_summaries: Vec<Value> = tasks
+        .into_iter()
+        .map(|((event_type, name), (mut runtimes, open))| {
+            runtimes.sort_unstable();
+            let total: u64 = runtimes.iter().sum();
+            let mut summary = json!({
+                "type": event_type,
+                "name": name,
+                "count": runtimes.len(),
+                "open": open,
+                "total": total,
+            });
+            if !runtimes.is_empty() {
+                summary["mean"] = json!(total / runtimes.len() as u64);
+                summary["min"] = json!(runtimes[0]);
+                summary["max"] = json!(runtimes[runtimes.len() - 1]);
+                summary["p50"] = json!(quantile(&runtimes, 0.5));
+                summary["p95"] = json!(quantile(&runtimes, 0.95));
+                summary["p99"] = json!(quantile(&runtimes, 0.99));
+            }
+            summary
+        })
+        .collect();
+    task_summaries.sort_by_key(|summary| std::cmp::Reverse(summary["total"].as_u64()));
+
+    for sample in dump_counters(dump) {
+        counters
+            .entry(sample["name"].as_str().unwrap_or("unknown").to_string())
+            .or_default()
+            .push(sample["value"].as_u64().unwrap_or(0));
+    }
+
+    let counter_summaries: Map<String, Value> = counters
+        .into_iter()
+        .map(|(name, values)| {
+            let summary = json!({
+                "samples": values.len(),
+                "last": values.last(),
+                "min": values.iter().min(),
+                "max": values.iter().max(),
+            });
+            (name, summary)
+        })
+        .collect();
+
+    json!({
+        "tasks": task_summaries,
+        "transactions": statuses,
+        "counters": counter_summaries,
+    })
+}
+
+/// Returns a statistical summary of the current profiling data as a JSON string.
+///
+/// Tasks are aggregated per name (per type for transactions and commits, and per base name
+/// for multi tasks), and sorted by total runtime. All durations are in nanoseconds.
+///
+/// # Returns
+///
+/// * `String` - A pretty-printed JSON string with the following structure:
+///
+/// ```json
+/// {
+///   "tasks": [
+///     {
+///       "type": "transaction",
+///       "name": "transaction",
+///       "count": 1000,
+///       "open": 0,
+///       "total": 31286000,
+///       "mean": 31286,
+///       "min": 20110,
+///       "max": 163541,
+///       "p50": 28209,
+///       "p95": 61020,
+///       "p99": 120448
+///     }
+///   ],
+///   "transactions": { "success": 990, "revert": 10 },
+///   "counters": {
+///     "rss_bytes": { "samples": 42, "last": 73400320, "min": 70254592, "max": 73400320 }
+///   }
+/// }
+/// ```
+///
+/// # Examples
+///
+/// ```rust
+/// use altius_benchtools::profiler;
+///
+/// for _ in 0..10 {
+///     profiler::start("query");
+///     // ... perform query ...
+///     profiler::end("query");
+/// }
+/// println!("{}", profiler::summary());
+/// ```
+pub fn summary() -> String {
+    serde_json::to_string_pretty(&summarize(&dump_value())).unwrap()
+}
@@ -0,0 +1,319 @@
+//! Tabular export of profiling data for dataframe tools such as pandas or DuckDB.
+//!
+//! Every event of a dump is flattened to one row with the columns
+//!
+//! - `thread` - index of the thread in the dump (empty for counter samples)
+//! - `type`, `name`, `start`, `end`, `runtime`, `status` - `name` holds the `"tx"` of
+//!   transaction and commit events
+//! - `cpu_runtime`, `value` and `perf.<event>` - only if any event has them
+//! - `detail.<key>` - the notes of the event; nested objects are flattened to
+//!   `detail.<key>.<nested key>`, arrays are kept as JSON text
+//!
+//! Dots and backslashes in keys are escaped with a backslash, so that a note key `"a.b"`
+//! (`detail.a\.b`) and a nested key `b` of a note `"a"` (`detail.a.b`) remain distinct
+//! columns.
+//!
+//! The table can be written as CSV, or as Apache Parquet with the `parquet` feature.
+
+use super::{dump_counters, dump_value};
+use serde_json::{Map, Value};
+use std::{collections::BTreeSet, fs::File, io::Write};
+#[cfg(feature = "parquet")]
+use {
+    arrow_array::{
+        ArrayRef, BooleanArray, Float64Array, Int64Array, RecordBatch, StringArray, UInt64Array,
+    },
+    arrow_schema::{Field, Schema},
+    parquet::{arrow::ArrowWriter, basic::Compression, file::properties::WriterProperties},
+    std::{io::Error, sync::Arc},
+};
+
+const BASE_COLUMNS: [&str; 7] = [
+    "thread", "type", "name", "start", "end", "runtime", "status",
+];
+
+/// Escapes the separator of flattened column names in a key
+fn escape_key(key: &str) -> String {
+    key.replace('\\', "\\\\").replace('.', "\\.")
+}
+
+/// Flattens nested objects into `prefix.key` entries, with escaped keys
+fn flatten_into(prefix: &str, value: &Value, output: &mut Map<String, Value>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                flatten_into(&format!("{}.{}", prefix, escape_key(key)), value, output);
+            }
+        }
+        other => {
+            output.insert(prefix.to_string(), other.clone());
+        }
+    }
+}
+
+/// Flattens the events of a dump into named columns and rows of cells.
+///
+/// With `detail_keys`, only the given detail keys (before flattening, e.g. `"gas"` for
+/// `detail.gas` and `detail.gas.*`) become columns; otherwise every detail key found in
+/// the dump does.
+fn flatten(dump: &Value, detail_keys: Option<&[&str]>) -> (Vec<String>, Vec<Vec<Value>>) {
+    let mut records = vec![];
+    let threads = dump
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_array)
+        .enumerate()
+        .map(|(thread_idx, events)| (Some(thread_idx), events.as_slice()));
+    // counter samples belong to no thread
+    for (thread_idx, events) in threads.chain([(None, dump_counters(dump))]) {
+        for event in events {
+            let mut record = Map::new();
+            if let Some(thread_idx) = thread_idx {
+                record.insert("thread".to_string(), thread_idx.into());
+            }
+            for key in [
+                "type",
+                "start",
+                "end",
+                "runtime",
+                "status",
+                "cpu_runtime",
+                "value",
+            ] {
+                if let Some(value) = event.get(key) {
+                    record.insert(key.to_string(), value.clone());
+                }
+            }
+            let name = match event.get("name") {
+                Some(name) => name.clone(),
+                None => event["tx"].clone(),
+            };
+            record.insert("name".to_string(), name);
+            if let Some(perf) = event.get("perf") {
+                flatten_into("perf", perf, &mut record);
+            }
+            for (key, value) in event["detail"].as_object().into_iter().flatten() {
+                let selected = match detail_keys {
+                    Some(keys) => keys.contains(&key.as_str()),
+                    None => true,
+                };
+                if selected {
+                    flatten_into(&format!("detail.{}", escape_key(key)), value, &mut record);
+                }
+            }
+            records.push(record);
+        }
+    }
+
+    let mut columns: Vec<String> = BASE_COLUMNS.iter().map(|c| c.to_string()).collect();
+    for optional in ["cpu_runtime", "value"] {
+        if records
+            .iter()
+            .any(|record| record.get(optional).is_some_and(|value| !value.is_null()))
+        {
+            columns.push(optional.to_string());
+        }
+    }
+    let extra: BTreeSet<&String> = records
+        .iter()
+        .flat_map(Map::keys)
+        .filter(|key| key.starts_with("perf.") || key.starts_with("detail."))
+        .collect();
+    // perf columns sort before detail columns
+    columns.extend(extra.into_iter().cloned());
+
+    let rows = records
+        .iter()
+        .map(|record| {
+            columns
+                .iter()
+                .map(|column| record.get(column).cloned().unwrap_or(Value::Null))
+                .collect()
+        })
+        .collect();
+    (columns, rows)
+}
+
+/// Renders a cell as text: empty for null, plain strings, JSON for everything else
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(string) => string.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Quotes a CSV field if needed (RFC 4180)
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Converts a dump produced by [`dump()`](super::dump) to CSV, one row per event.
+///
+/// See the [module documentation](self) for the columns.
+///
+/// # Arguments
+///
+/// * `dump` - The parsed output of [`dump()`](super::dump)
+/// * `detail_keys` - The detail keys to export as columns, or `None` for all of them
+///
+/// # Returns
+///
+/// * `String` - The CSV text, with a header row
+///
+/// # Examples
+///
+/// ```rust
+/// use altius_benchtools::profiler;
+///
+/// let dump = serde_json::from_str(&profiler::dump()).unwrap();
+/// let csv = profiler::to_csv(&dump, Some(&["gas_used"]));
+/// assert!(csv.starts_with("thread,type,name,start,end,runtime,status"));
+/// ```
+pub fn to_csv(dump: &Value, detail_keys: Option<&[&str]>) -> String {
+    let (columns, rows) = flatten(dump, detail_keys);
+    let mut csv = String::new();
+    let header: Vec<String> = columns.iter().map(|column| csv_field(column)).collect();
+    csv += &header.join(",");
+    csv += "\n";
+    for row in rows {
+        let fields: Vec<String> = row.iter().map(|cell| csv_field(&cell_text(cell))).collect();
+        csv += &fields.join(",");
+        csv += "\n";
+    }
+    csv
+}
+
+/// Dumps the profiler data to a CSV file at the specified path, one row per event.
+///
+/// All detail keys are exported as columns; use [`to_csv()`] to select them.
+///
+/// # Arguments
+///
+/// * `output_path` - The path where the CSV file should be written
+///
+/// # Panics
+///
+/// * Panics if the file cannot be created or written to
+///
+/// # Examples
+///
+/// ```rust
+/// use altius_benchtools::profiler;
+///
+/// // After some profiling...
+/// profiler::dump_csv("profile_results.csv");
+/// // then, e.g. in Python: pandas.read_csv("profile_results.csv")
+/// ```
+pub fn dump_csv(output_path: &str) {
+    let csv = to_csv(&dump_value(), None);
+    let mut file = File::create(output_path).unwrap();
+    file.write_all(csv.as_bytes()).unwrap();
+}
+
+/// Builds an Arrow array from a column of cells, choosing the narrowest type that
+/// holds all non-null cells and falling back to strings
+#[cfg(feature = "parquet")]
+fn column_array(cells: &[&Value]) -> ArrayRef {
+    let present = || cells.iter().filter(|cell| !cell.is_null());
+    if present().all(|cell| cell.is_u64()) {
+        Arc::new(UInt64Array::from_iter(cells.iter().map(|c| c.as_u64())))
+    } else if present().all(|cell| cell.is_i64()) {
+        Arc::new(Int64Array::from_iter(cells.iter().map(|c| c.as_i64())))
+    } else if present().all(|cell| cell.is_number()) {
+        Arc::new(Float64Array::from_iter(cells.iter().map(|c| c.as_f64())))
+    } else if present().all(|cell| cell.is_boolean()) {
+        Arc::new(BooleanArray::from_iter(cells.iter().map(|c| c.as_bool())))
+    } else {
+        Arc::new(StringArray::from_iter(
+            cells
+                .iter()
+                .map(|cell| (!cell.is_null()).then(|| cell_text(cell))),
+        ))
+    }
+}
+
+/// Writes a dump produced by [`dump()`](super::dump) to an Apache Parquet
+/// file, one row per event (`parquet` feature).
+///
+/// The columns are the same as in [`to_csv()`]. Each column is typed as
+/// unsigned or signed 64-bit integer, double, boolean or string, whichever is the
+/// narrowest type holding all of its values. The file is Snappy-compressed.
+///
+/// # Arguments
+///
+/// * `dump` - The parsed output of [`dump()`](super::dump)
+/// * `detail_keys` - The detail keys to export as columns, or `None` for all of them
+/// * `output_path` - The path where the Parquet file should be written
+///
+/// # Errors
+///
+/// * Returns an error if the file cannot be created or written to
+///
+/// # Examples
+///
+/// ```rust
+/// use altius_benchtools::profiler;
+///
+/// let dump = serde_json::from_str(&profiler::dump()).unwrap();
+/// profiler::write_parquet(&dump, None, "profile_results.parquet").unwrap();
+/// // then, e.g. in DuckDB: SELECT * FROM 'profile_results.parquet'
+/// ```
+#[cfg(feature = "parquet")]
+pub fn write_parquet(
+    dump: &Value,
+    detail_keys: Option<&[&str]>,
+    output_path: &str,
+) -> Result<(), Error> {
+    let (columns, rows) = flatten(dump, detail_keys);
+    let mut fields = vec![];
+    let mut arrays = vec![];
+    for (index, column) in columns.iter().enumerate() {
+        let cells: Vec<&Value> = rows.iter().map(|row| &row[index]).collect();
+        let array = column_array(&cells);
+        fields.push(Field::new(column, array.data_type().clone(), true));
+        arrays.push(array);
+    }
+    let schema = Arc::new(Schema::new(fields));
+    let batch = RecordBatch::try_new(schema.clone(), arrays).map_err(Error::other)?;
+
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let file = File::create(output_path)?;
+    let mut writer = ArrowWriter::try_new(file, schema, Some(properties)).map_err(Error::other)?;
+    writer.write(&batch).map_err(Error::other)?;
+    writer.close().map_err(Error::other)?;
+    Ok(())
+}
+
+/// Dumps the profiler data to an Apache Parquet file at the specified path, one row per
+/// event (`parquet` feature).
+///
+/// All detail keys are exported as columns; use [`write_parquet()`] to select them.
+///
+/// # Arguments
+///
+/// * `output_path` - The path where the Parquet file should be written
+///
+/// # Panics
+///
+/// * Panics if the file cannot be created or written to
+///
+/// # Examples
+///
+/// ```rust
+/// use altius_benchtools::profiler;
+///
+/// // After some profiling...
+/// profiler::dump_parquet("profile_results.parquet");
+/// ```
+#[cfg(feature = "parquet")]
+pub fn dump_parquet(output_path: &str) {
+    write_parquet(&dump_value(), None, output_path).unwrap();
+}
@@ -0,0 +1,174 @@
+//! Detection of unbalanced or leaked tasks.
+//!
+//! A task that is started but never ended still appears in a dump, with `"end": null` and
+//! a runtime measured up to the time of the dump. Such events skew runtime statistics and
+//! usually point at a missing [`end()`](super::end) in the instrumentation, e.g. on an
+//! early return or error path. The validation pass reports
+//!
+//! - **open tasks**: events that were never ended (the entries created by
+//!   [`note_str_unchecked()`](super::note_str_unchecked) are point annotations, ended when
+//!   they are created)
+//! - **open multi tasks**: [`start_multi()`](super::start_multi) instances (named
+//!   `<base>-[<index>]`) that were never ended, which also make the next `start_multi()`
+//!   of the same base name panic
+//! - **long spans**: ended events whose runtime exceeds [`LONG_SPAN_FACTOR`] times the
+//!   median runtime of their group (as in [`summarize()`](super::summarize)), for groups of
+//!   at least [`LONG_SPAN_MIN_SAMPLES`] ended events
+
+use super::{dump_value, summary::group_of};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+/// Runtime, as a multiple of the group median, above which an event is reported as long
+pub const LONG_SPAN_FACTOR: u64 = 10;
+
+/// Minimum number of ended events in a group for its long spans to be reported
+pub const LONG_SPAN_MIN_SAMPLES: usize = 5;
+
+/// Returns `(base name, index)` if the name is that of a [`super::start_multi()`] instance
+fn multi_instance(name: &str) -> Option<(&str, u64)> {
+    let (base, index) = name.strip_suffix(']')?.rsplit_once("-[")?;
+    Some((base, index.parse().ok()?))
+}
+
+/// Validates a dump produced by [`dump()`](super::dump), reporting tasks that were never
+/// ended and suspiciously long spans.
+///
+/// See the [module documentation](self) for the checks.
+///
+/// # Arguments
+///
+/// * `dump` - The parsed output of [`dump()`](super::dump)
+///
+/// # Returns
+///
+/// * `Value` - The validation report, with `"ok": true` if no issue was found:
+///
+/// ```json
+/// {
+///   "ok": false,
+///   "open_tasks": [{ "thread": 0, "type": "other", "name": "fetch", "start": 1000, "open_for": 52000 }],
+///   "open_multi_tasks": [{ "task": "batch", "instance": 3, "start": 8000, "open_for": 45000 }],
+///   "long_spans": [{ "thread": 1, "type": "transaction", "name": "0x01", "start": 2000,
+///                    "runtime": 900000, "group_median": 30000 }]
+/// }
+/// ```
+pub fn validate(dump: &Value) -> Value {
+    let mut open_tasks = vec![];
+    let mut open_multi_tasks = vec![];
+    let mut groups: BTreeMap<(String, String), Vec<(usize, &Value)>> = BTreeMap::new();
+
+    for (thread_idx, events) in dump
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_array)
+        .enumerate()
+    {
+        for event in events {
+            let name = match event.get("name") {
+                Some(name) => name,
+                None => &event["tx"],
+            };
+            if !event["end"].is_null() {
+                groups
+                    .entry(group_of(event))
+                    .or_default()
+                    .push((thread_idx, event));
+                continue;
+            }
+            match name.as_str().and_then(multi_instance) {
+                Some((base, index)) => open_multi_tasks.push(json!({
+                    "task": base,
+                    "instance": index,
+                    "start": event["start"],
+                    "open_for": event["runtime"],
+                })),
+                None => open_tasks.push(json!({
+                    "thread": thread_idx,
+                    "type": event["type"],
+                    "name": name,
+                    "start": event["start"],
+                    "open_for": event["runtime"],
+                })),
+            }
+        }
+    }
+
+    let mut long_spans = vec![];
+    for events in groups.values() {
+        if events.len() < LONG_SPAN_MIN_SAMPLES {
+            continue;
+        }
+        let runtime = |event: &Value| event["runtime"].as_u64().unwrap_or(0);
+        let mut runtimes: Vec<u64> = events.iter().map(|(_, event)| runtime(event)).collect();
+        runtimes.sort_unstable();
+        let median = runtimes[(runtimes.len() - 1) / 2];
+        for (thread_idx, event) in events {
+            if runtime(event) > median.max(1) * LONG_SPAN_FACTOR {
+                long_spans.push(json!({
+                    "thread": thread_idx,
+                    "type": event["type"],
+                    "name": event.get("name").unwrap_or(&event["tx"]),
+                    "start": event["start"],
+                    "runtime": event["runtime"],
+                    "group_median": median,
+                }));
+            }
+        }
+    }
+    long_spans.sort_by_key(|span| std::cmp::Reverse(span["runtime"].as_u64()));
+
+    json!({
+        "ok": open_tasks.is_empty() && open_multi_tasks.is_empty() && long_spans.is_empty(),
+        "open_tasks": open_tasks,
+        "open_multi_tasks": open_multi_tasks,
+        "long_spans": long_spans,
+    })
+}
+
+/// Validates the current profiling data and returns the report as a JSON string.
+///
+/// This is [`validate()`] applied to the current [`dump()`](super::dump).
+///
+/// # Returns
+///
+/// * `String` - A pretty-printed JSON string containing the validation report
+///
+/// # Examples
+///
+/// ```rust
+/// use altius_benchtools::profiler;
+///
+/// profiler::start("leaked");
+/// // ... `end("leaked")` is never called ...
+///
+/// let report: serde_json::Value = serde_json::from_str(&profiler::validation()).unwrap();
+/// assert_eq!(report["ok"], false);
+/// ```
+pub fn validation() -> String {
+    serde_json::to_string_pretty(&validate(&dump_value())).unwrap()
+}
+
+/// Prints a warning to stderr for every issue of a validation report
+pub(super) fn warn(report: &Value) {
+    let list = |key: &str| report[key].as_array().cloned().unwrap_or_default();
+    for task in list("open_tasks") {
+        eprintln!(
+            "profiler: warning: task {} on thread {} was never ended (open for {} ns)",
+            task["name"], task["thread"], task["open_for"]
+        );
+    }
+    for task in list("open_multi_tasks") {
+        eprintln!(
+            "profiler: warning: multi task {} instance {} was never ended (open for {} ns)",
+            task["task"], task["instance"], task["open_for"]
+        );
+    }
+    for span in list("long_spans") {
+        eprintln!(
+            "profiler: warning: task {} on thread {} ran for {} ns, over {}x its group median of {} ns",
+            span["name"], span["thread"], span["runtime"], LONG_SPAN_FACTOR, span["group_median"]
+        );
+    }
+}
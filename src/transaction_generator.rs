@@ -1,18 +1,49 @@
-use crate::constants::ERC20_USDC_DEPLOYED_BYTECODE;
+use crate::constants::{
+    AMM_PAIR_DEPLOYED_BYTECODE, CREATE2_FACTORY_ADDRESS, CREATE2_FACTORY_DEPLOYED_BYTECODE,
+    ERC1155_DEPLOYED_BYTECODE, ERC20_USDC_DEPLOYED_BYTECODE, ERC721_DEPLOYED_BYTECODE,
+    STORAGE_ACCESS_DEPLOYED_BYTECODE,
+};
 use ethers::{
-    core::k256::ecdsa::SigningKey,
+    core::{k256::ecdsa::SigningKey, rand::SeedableRng as _},
     prelude::{rand as ethers_rand, LocalWallet, Signer},
     signers::Wallet,
-    types::Address,
-    utils::{hex, keccak256},
+    types::{Address, H256, U256},
+    utils::{get_contract_address, get_create2_address, hex, keccak256, rlp::RlpStream},
 };
-use rand::Rng;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
 use serde_json::{json, Map, Value};
-use std::io::{Error, ErrorKind};
+#[cfg(feature = "blob")]
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{HashMap, HashSet},
+    io::{Error, ErrorKind},
+    ops::RangeInclusive,
+    str::FromStr,
+};
 
 const DEFAULT_BALANCE_HEX: &str = "0x056bc75e2d63100000";
 const DEFAULT_BALANCE: u128 = 100 * ONE_ETHER;
 const ONE_ETHER: u128 = 1_000_000_000_000_000_000;
+const AMM_RESERVE: u128 = 1_000_000 * ONE_ETHER;
+const CONTRACT_DEPLOYER: &str = "0x00000000000000000000000000000000a770a770";
+/// Maximum size of deployed code (EIP-170)
+const MAX_CODE_SIZE: usize = 24_576;
+const MAX_ALLOWANCE_HEX: &str =
+    "0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff";
+/// Address the `blob` pattern sends its transactions to, as rollup batchers do to their
+/// batch inbox
+#[cfg(feature = "blob")]
+const BLOB_INBOX: &str = "0x000000000000000000000000000000000000b10b";
+/// Blob gas of a blob, the size of a blob in bytes (EIP-4844)
+#[cfg(feature = "blob")]
+const GAS_PER_BLOB: u128 = 131_072;
+#[cfg(feature = "blob")]
+const MAX_BLOBS_PER_TX: u128 = 6;
+/// Chain id the transactions and authorizations are signed for by default, the one of
+/// state tests
+const DEFAULT_CHAIN_ID: u64 = 1;
+/// Intrinsic gas of an EIP-7702 authorization to an account that does not exist yet
+const GAS_PER_AUTHORIZATION: u128 = 25_000;
 
 trait ToHex {
     fn to_hex(&self) -> String;
@@ -41,7 +72,22 @@ impl ToHex for Wallet<SigningKey> {
     }
 }
 
-fn random_near(num: u64, rng: &mut rand::rngs::ThreadRng) -> u64 {
+/// Parses a quantity written by [`ToHex`], if it fits in a `u128`
+fn from_hex(value: &Value) -> Option<u128> {
+    u128::from_str_radix(value.as_str()?.trim_start_matches("0x"), 16).ok()
+}
+
+/// Pads a storage slot to a 32-byte access list key
+fn access_list_key(slot: &str) -> String {
+    format!("0x{:0>64}", slot.trim_start_matches("0x"))
+}
+
+/// Returns the address held by a 32-byte calldata or storage word
+fn word_address(word: &str) -> Address {
+    Address::from_str(&word[word.len() - 40..]).unwrap()
+}
+
+fn random_near(num: u64, rng: &mut StdRng) -> u64 {
     let random_small = ((rng.random_range(0..num) * num) as f64).sqrt();
     num - 1 - random_small as u64
 }
@@ -54,6 +100,41 @@ fn get_storage_slot_balance(address: Address) -> String {
     "0x".to_owned() + &hex::encode(slot)
 }
 
+fn get_storage_slot_mapping(key: &str, slot: u128) -> String {
+    let slot = keccak256(hex::decode(format!("{:0>64}{:064x}", key, slot)).unwrap());
+    "0x".to_owned() + &hex::encode(slot)
+}
+
+fn get_storage_slot_allowance(owner: Address, spender: Address) -> String {
+    let owner_slot = keccak256(
+        hex::decode(format!(
+            "000000000000000000000000{}{:064x}",
+            owner.to_hex().strip_prefix("0x").unwrap(),
+            1
+        ))
+        .unwrap(),
+    );
+    let slot_part_0 =
+        "000000000000000000000000".to_owned() + spender.to_hex().strip_prefix("0x").unwrap();
+    let slot = keccak256(hex::decode(slot_part_0 + &hex::encode(owner_slot)).unwrap());
+    "0x".to_owned() + &hex::encode(slot)
+}
+
+fn set_storage_prestate(
+    pre: &mut Map<String, Value>,
+    contract: Address,
+    slot: String,
+    value: &str,
+) {
+    pre.get_mut(&contract.to_hex())
+        .unwrap()
+        .get_mut("storage")
+        .unwrap()
+        .as_object_mut()
+        .unwrap()
+        .insert(slot, Value::from(value));
+}
+
 fn add_erc20_balance_prestate(pre: &mut Map<String, Value>, erc20: Address, account: Address) {
     pre.get_mut(&erc20.to_hex())
         .unwrap()
@@ -67,6 +148,348 @@ fn add_erc20_balance_prestate(pre: &mut Map<String, Value>, erc20: Address, acco
         );
 }
 
+fn get_storage_slot_erc1155_balance(account: Address, token_id: u128) -> String {
+    let id_slot = get_storage_slot_mapping(&format!("{:x}", token_id), 0);
+    let slot = keccak256(
+        hex::decode(format!(
+            "000000000000000000000000{}{}",
+            account.to_hex().strip_prefix("0x").unwrap(),
+            id_slot.strip_prefix("0x").unwrap()
+        ))
+        .unwrap(),
+    );
+    "0x".to_owned() + &hex::encode(slot)
+}
+
+fn add_erc1155_balance_prestate(
+    pre: &mut Map<String, Value>,
+    erc1155: Address,
+    account: Address,
+    token_id: u128,
+) {
+    pre.get_mut(&erc1155.to_hex())
+        .unwrap()
+        .get_mut("storage")
+        .unwrap()
+        .as_object_mut()
+        .unwrap()
+        .insert(
+            get_storage_slot_erc1155_balance(account, token_id),
+            Value::from(DEFAULT_BALANCE_HEX),
+        );
+}
+
+fn fees_overflow() -> Error {
+    Error::new(
+        ErrorKind::InvalidInput,
+        "the fees of a sender overflow its balance, lower the gas price or the blob fee",
+    )
+}
+
+/// Adds `amount` to the balance of `account` in `pre`, if it has one, failing if the
+/// balance overflows
+fn add_balance_prestate(
+    pre: &mut Map<String, Value>,
+    account: &str,
+    amount: u128,
+) -> Result<(), Error> {
+    let Some(account) = pre.get_mut(account) else {
+        return Ok(());
+    };
+    if let Some(balance) = from_hex(&account["balance"]) {
+        let balance = balance.checked_add(amount).ok_or_else(fees_overflow)?;
+        account["balance"] = balance.to_hex().into();
+    }
+    Ok(())
+}
+
+/// Returns a random blob, its KZG commitment, its KZG proof and its versioned hash, as hex
+#[cfg(feature = "blob")]
+fn random_blob(rng: &mut StdRng) -> [String; 4] {
+    let mut bytes = vec![0u8; GAS_PER_BLOB as usize];
+    rng.fill(&mut bytes[..]);
+    // every 32-byte field element must be below the BLS12-381 modulus
+    for field_element in bytes.chunks_mut(32) {
+        field_element[0] = 0;
+    }
+    let blob = c_kzg::Blob::from_bytes(&bytes).unwrap();
+    let settings = c_kzg::ethereum_kzg_settings(0);
+    let commitment = settings.blob_to_kzg_commitment(&blob).unwrap().to_bytes();
+    let proof = settings
+        .compute_blob_kzg_proof(&blob, &commitment)
+        .unwrap()
+        .to_bytes();
+    let mut versioned_hash: [u8; 32] = Sha256::digest(commitment.as_slice()).into();
+    versioned_hash[0] = 0x01;
+    [
+        format!("0x{}", hex::encode(bytes)),
+        format!("0x{}", hex::encode(commitment.as_slice())),
+        format!("0x{}", hex::encode(proof.as_slice())),
+        format!("0x{}", hex::encode(versioned_hash)),
+    ]
+}
+
+/// Signs an EIP-7702 authorization of `authority` delegating its code to `delegate`, valid
+/// when the nonce of `authority` is `nonce`
+fn sign_authorization(
+    authority: &Wallet<SigningKey>,
+    chain_id: u64,
+    delegate: Address,
+    nonce: u64,
+) -> Value {
+    let mut rlp = RlpStream::new_list(3);
+    rlp.append(&chain_id);
+    rlp.append(&delegate);
+    rlp.append(&nonce);
+    // the authorization magic byte, followed by the RLP tuple
+    let hash = keccak256([&[0x05], rlp.as_raw()].concat());
+    let signature = authority.sign_hash(H256::from(hash)).unwrap();
+    let y_parity = u128::from(signature.v - 27).to_hex();
+    json!({
+        "address": delegate.to_hex(),
+        "chainId": u128::from(chain_id).to_hex(),
+        "nonce": u128::from(nonce).to_hex(),
+        "r": format!("0x{:x}", signature.r),
+        "s": format!("0x{:x}", signature.s),
+        "signer": authority.address().to_hex(),
+        "v": y_parity,
+        "yParity": y_parity,
+    })
+}
+
+/// Signs `tx`, as generated by [`TransactionGenerator`], with its `secretKey` for
+/// `chain_id`, and returns its `hash` and its `raw` RLP encoding, as accepted by
+/// `eth_sendRawTransaction`.
+///
+/// The transaction type follows from the fields of `tx`: legacy transactions are signed
+/// with EIP-155 replay protection, and blob transactions carrying their sidecars are
+/// encoded in their network form, the hash covering the transaction without the sidecars.
+pub fn sign_transaction(tx: &Value, chain_id: u64) -> Result<Value, Error> {
+    fn invalid(field: &str) -> Error {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("transaction has no valid `{}`", field),
+        )
+    }
+    fn str_field<'a>(value: &'a Value, field: &str) -> Result<&'a str, Error> {
+        value[field].as_str().ok_or_else(|| invalid(field))
+    }
+    fn bytes(value: &Value, field: &str) -> Result<Vec<u8>, Error> {
+        hex::decode(str_field(value, field)?).map_err(|_| invalid(field))
+    }
+    fn word(value: &Value, field: &str) -> Result<H256, Error> {
+        H256::from_str(value.as_str().unwrap_or("")).map_err(|_| invalid(field))
+    }
+    fn quantity(value: &Value, field: &str) -> Result<U256, Error> {
+        match str_field(value, field)?.trim_start_matches("0x") {
+            "" => Ok(U256::zero()),
+            digits => U256::from_str_radix(digits, 16).map_err(|_| invalid(field)),
+        }
+    }
+    fn address(value: &Value, field: &str) -> Result<Address, Error> {
+        Address::from_str(str_field(value, field)?).map_err(|_| invalid(field))
+    }
+    let list = |field: &str| tx[field].as_array().ok_or_else(|| invalid(field));
+    let tx_type: u8 = if tx.get("authorizationList").is_some() {
+        4
+    } else if tx.get("blobVersionedHashes").is_some() {
+        3
+    } else if tx.get("maxFeePerGas").is_some() {
+        2
+    } else if tx.get("accessList").is_some() {
+        1
+    } else {
+        0
+    };
+
+    // the fields of the transaction, before the signature
+    let mut body = RlpStream::new();
+    if tx_type != 0 {
+        body.append(&chain_id);
+    }
+    body.append(&quantity(tx, "nonce")?);
+    if tx_type >= 2 {
+        body.append(&quantity(tx, "maxPriorityFeePerGas")?);
+        body.append(&quantity(tx, "maxFeePerGas")?);
+    } else {
+        body.append(&quantity(tx, "gasPrice")?);
+    }
+    body.append(&quantity(tx, "gasLimit")?);
+    match str_field(tx, "to")? {
+        "" => body.append_empty_data(),
+        _ => body.append(&address(tx, "to")?),
+    };
+    body.append(&quantity(tx, "value")?);
+    body.append(&bytes(tx, "data")?);
+    if tx_type != 0 {
+        body.begin_unbounded_list();
+        for entry in list("accessList")? {
+            body.begin_list(2);
+            body.append(&address(entry, "address")?);
+            body.begin_unbounded_list();
+            let keys = entry["storageKeys"].as_array();
+            for key in keys.ok_or_else(|| invalid("accessList"))? {
+                body.append(&word(key, "accessList")?);
+            }
+            body.finalize_unbounded_list();
+        }
+        body.finalize_unbounded_list();
+    }
+    if tx_type == 3 {
+        body.append(&quantity(tx, "maxFeePerBlobGas")?);
+        body.begin_unbounded_list();
+        for hash in list("blobVersionedHashes")? {
+            body.append(&word(hash, "blobVersionedHashes")?);
+        }
+        body.finalize_unbounded_list();
+    }
+    if tx_type == 4 {
+        body.begin_unbounded_list();
+        for authorization in list("authorizationList")? {
+            body.begin_list(6);
+            body.append(&quantity(authorization, "chainId")?);
+            body.append(&address(authorization, "address")?);
+            body.append(&quantity(authorization, "nonce")?);
+            body.append(&quantity(authorization, "v")?);
+            body.append(&quantity(authorization, "r")?);
+            body.append(&quantity(authorization, "s")?);
+        }
+        body.finalize_unbounded_list();
+    }
+    let body = body.out();
+
+    let wallet: LocalWallet = str_field(tx, "secretKey")?
+        .parse()
+        .map_err(|_| invalid("secretKey"))?;
+    // typed transactions are the type byte followed by the RLP list of their fields
+    let envelope = |fields: &[u8]| {
+        let mut list = RlpStream::new();
+        list.begin_unbounded_list();
+        list.append_raw(fields, 0);
+        list.finalize_unbounded_list();
+        let list = list.out().to_vec();
+        match tx_type {
+            0 => list,
+            _ => [vec![tx_type], list].concat(),
+        }
+    };
+    let mut unsigned = body.to_vec();
+    if tx_type == 0 {
+        // EIP-155
+        let mut replay_protection = RlpStream::new();
+        replay_protection.append(&chain_id);
+        replay_protection.append(&0u8);
+        replay_protection.append(&0u8);
+        unsigned.extend_from_slice(&replay_protection.out());
+    }
+    let signature = wallet
+        .sign_hash(H256::from(keccak256(envelope(&unsigned))))
+        .map_err(|_| invalid("secretKey"))?;
+    let y_parity = signature.v - 27;
+
+    let mut signed = RlpStream::new();
+    signed.append_raw(&body, 0);
+    match tx_type {
+        0 => signed.append(&(y_parity + 35 + 2 * chain_id)),
+        _ => signed.append(&y_parity),
+    };
+    signed.append(&signature.r);
+    signed.append(&signature.s);
+    let signed = envelope(&signed.out());
+    let hash = keccak256(&signed);
+
+    let raw = match tx.get("blobs") {
+        // the network form wraps the transaction with its blobs, commitments and proofs
+        Some(_) if tx_type == 3 => {
+            let mut network = RlpStream::new();
+            network.begin_unbounded_list();
+            network.append_raw(&signed[1..], 1);
+            for field in ["blobs", "commitments", "proofs"] {
+                network.begin_unbounded_list();
+                for item in list(field)? {
+                    let item = hex::decode(item.as_str().unwrap_or("")).map_err(|_| invalid(field));
+                    network.append(&item?);
+                }
+                network.finalize_unbounded_list();
+            }
+            network.finalize_unbounded_list();
+            [vec![3], network.out().to_vec()].concat()
+        }
+        _ => signed,
+    };
+    Ok(json!({
+        "hash": format!("0x{}", hex::encode(hash)),
+        "raw": format!("0x{}", hex::encode(raw)),
+    }))
+}
+
+fn amm_swap(amount_in: u128, zero_for_one: bool) -> String {
+    format!("0x2aea6605{:064x}{:064x}", amount_in, zero_for_one as u8)
+}
+
+fn erc721_transfer_from(from: Address, to: Address, token_id: u128) -> String {
+    format!(
+        "0x23b872dd000000000000000000000000{}000000000000000000000000{}{:064x}",
+        from.to_hex().strip_prefix("0x").unwrap(),
+        to.to_hex().strip_prefix("0x").unwrap(),
+        token_id
+    )
+}
+
+fn erc721_approve(to: Address, token_id: u128) -> String {
+    format!(
+        "0x095ea7b3000000000000000000000000{}{:064x}",
+        to.to_hex().strip_prefix("0x").unwrap(),
+        token_id
+    )
+}
+
+fn erc1155_safe_batch_transfer_from(
+    from: Address,
+    to: Address,
+    token_ids: &[u128],
+    values: &[u128],
+) -> String {
+    let words = token_ids.len() as u128 + 1;
+    let mut data = format!(
+        "0x2eb2c2d6000000000000000000000000{}000000000000000000000000{}{:064x}{:064x}{:064x}",
+        from.to_hex().strip_prefix("0x").unwrap(),
+        to.to_hex().strip_prefix("0x").unwrap(),
+        0xa0,
+        0xa0 + 0x20 * words,
+        0xa0 + 0x40 * words
+    );
+    for array in [token_ids, values] {
+        data += &format!("{:064x}", array.len());
+        for item in array {
+            data += &format!("{:064x}", item);
+        }
+    }
+    // empty `data`
+    data + &format!("{:064x}", 0)
+}
+
+/// Returns the slot of the storage access contract for the key with the given index, a
+/// hash of the index with the top bit (the write flag) cleared
+fn storage_access_slot(key_idx: u128) -> [u8; 32] {
+    let mut slot = keccak256(hex::decode(format!("{:064x}", key_idx)).unwrap());
+    slot[0] &= 0x7f;
+    slot
+}
+
+/// Returns init code running `constructor` (hex without `0x`), then returning `runtime`
+fn init_code(constructor: &str, runtime: &str) -> String {
+    // PUSH2 len DUP1 PUSH2 offset PUSH1 0 CODECOPY PUSH1 0 RETURN
+    let offset = constructor.len() / 2 + 13;
+    format!(
+        "0x{}61{:04x}8061{:04x}6000396000f3{}",
+        constructor,
+        runtime.len() / 2,
+        offset,
+        runtime
+    )
+}
+
 fn erc20_transfer(to: Address, value: u128) -> String {
     let to_string = to.to_hex();
     let to_without_hex = to_string.strip_prefix("0x").unwrap();
@@ -76,148 +499,937 @@ fn erc20_transfer(to: Address, value: u128) -> String {
     )
 }
 
-pub struct TransactionGenerator {
-    ethers_rng: ethers_rand::rngs::ThreadRng,
-    rng: rand::rngs::ThreadRng,
-    pre: Map<String, Value>,
-    transactions: Vec<Value>,
+/// Parameters of the skewed `zipf`, `hot-spot` and `amm` patterns.
+///
+/// The `zipf` and `hot-spot` patterns draw the sender and the receiver of every transaction
+/// from a shared pool of `accounts` funded accounts, so that a few hot accounts are touched
+/// by most of the transactions, as with popular tokens or pools in DeFi workloads. The
+/// `amm` pattern swaps on `pairs` AMM pairs, picked with the Zipf distribution.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkewConfig {
+    /// Size of the account pool; `None` for one account per transaction
+    pub accounts: Option<u128>,
+    /// Exponent `s` of the Zipf distribution: the account of rank `k` is picked with a
+    /// probability proportional to `1 / k^s` (`0.0` is uniform, higher is more skewed)
+    pub zipf_exponent: f64,
+    /// Fraction of the pool that is hot in the `hot-spot` pattern
+    pub hot_fraction: f64,
+    /// Probability that an account of a `hot-spot` transaction is picked from the hot set
+    pub hot_traffic: f64,
+    /// Number of AMM pairs of the `amm` pattern
+    pub pairs: u128,
 }
 
-impl TransactionGenerator {
-    pub fn new() -> Self {
+impl Default for SkewConfig {
+    /// 80% of the accounts picked in `hot-spot` transactions are among 1% of the pool
+    fn default() -> Self {
         Self {
-            ethers_rng: ethers_rand::thread_rng(),
-            rng: rand::rng(),
-            pre: Map::new(),
-            transactions: Vec::new(),
+            accounts: None,
+            zipf_exponent: 1.0,
+            hot_fraction: 0.01,
+            hot_traffic: 0.8,
+            pairs: 16,
         }
     }
+}
 
-    fn _check_tx_num(&self, num_transactions: u128, num_groups: u128) -> Result<(), Error> {
-        if num_transactions < num_groups {
-            Err(Error::new(
-                ErrorKind::InvalidInput,
-                "number of transactions must be greater than number of groups",
-            ))
-        } else {
-            Ok(())
+/// Parameters of the `erc1155-batch` pattern.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchConfig {
+    /// Number of token ids transferred by every `safeBatchTransferFrom` call
+    pub batch_size: u128,
+    /// Fraction of the token ids of every batch drawn from a pool of `batch_size` ids shared
+    /// by all transactions; the others are only transferred by that transaction (`0.0`
+    /// shares no token id, `1.0` draws every batch from the shared pool)
+    pub id_overlap: f64,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: 10,
+            id_overlap: 0.5,
         }
     }
+}
 
-    fn _check_m2m_params(&self, num_transactions: u128, conflict_rate: f64) -> Result<(), Error> {
-        if num_transactions <= 4 {
-            Err(Error::new(
-                ErrorKind::InvalidInput,
-                "number of transactions must be greater than 4",
-            ))
-        } else if conflict_rate < 0.0 || conflict_rate > 1.0 {
-            Err(Error::new(
+/// Parameters of the `storage` pattern.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StorageConfig {
+    /// Number of storage slots accessed by every transaction
+    pub slots_per_tx: u128,
+    /// Fraction of the slots of every transaction that are written (incremented), the
+    /// others being only read
+    pub write_ratio: f64,
+    /// Fraction of the slots of every transaction that are accessed by all transactions; the
+    /// others are only accessed by that transaction (`0.0` makes transactions independent,
+    /// `1.0` makes them all access the same slots)
+    pub key_overlap: f64,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            slots_per_tx: 10,
+            write_ratio: 0.5,
+            key_overlap: 0.1,
+        }
+    }
+}
+
+/// Type of the generated transactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TxType {
+    /// Legacy transactions with a `gasPrice`
+    #[default]
+    Legacy,
+    /// EIP-2930 (type 1) transactions with a `gasPrice` and an `accessList`
+    AccessList,
+    /// EIP-1559 (type 2) transactions with a `maxFeePerGas`, a `maxPriorityFeePerGas` and an
+    /// `accessList`
+    DynamicFee,
+}
+
+impl FromStr for TxType {
+    type Err = Error;
+
+    fn from_str(tx_type: &str) -> Result<Self, Error> {
+        match tx_type {
+            "legacy" | "0" => Ok(Self::Legacy),
+            "access-list" | "eip2930" | "1" => Ok(Self::AccessList),
+            "dynamic-fee" | "eip1559" | "2" => Ok(Self::DynamicFee),
+            _ => Err(Error::new(
                 ErrorKind::InvalidInput,
-                "conflict rate must be between 0.0 and 1.0",
-            ))
-        } else {
-            Ok(())
+                "Invalid transaction type. Available types are: 'legacy' (or '0'), \
+                'access-list' (or 'eip2930', '1'), 'dynamic-fee' (or 'eip1559', '2').",
+            )),
         }
     }
+}
 
-    fn deploy_erc20(&mut self) -> Result<Address, Error> {
-        let bytecode = ERC20_USDC_DEPLOYED_BYTECODE.to_string();
-        let contract_address = Address::from_slice(&[
-            179, 13, 249, 43, 177, 7, 230, 241, 228, 111, 125, 244, 253, 49, 163, 22, 206, 180,
-            231, 217,
-        ]);
-        self.pre.insert(
-            contract_address.to_hex(),
-            json!({
-                "balance": "0x00",
-                "code": bytecode,
-                "nonce": "0x00",
-                "storage": {}
-            }),
-        );
-        Ok(contract_address)
+/// Distribution a fee in wei per gas is drawn from, for every transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeDistribution {
+    /// The same fee for every transaction
+    Fixed(u128),
+    /// A fee drawn uniformly from `min..=max`
+    Uniform { min: u128, max: u128 },
+}
+
+impl FeeDistribution {
+    fn sample(&self, rng: &mut StdRng) -> u128 {
+        match *self {
+            Self::Fixed(fee) => fee,
+            Self::Uniform { min, max } => rng.random_range(min..=max),
+        }
     }
+}
 
-    fn generate_pattern_o2m(
-        &mut self,
-        num_transactions: u128,
-        num_groups: u128,
-        is_erc20: bool,
-    ) -> Result<(), Error> {
-        self._check_tx_num(num_transactions, num_groups)?;
-        let erc20_address = if is_erc20 {
-            Some(self.deploy_erc20()?)
-        } else {
-            None
+impl FromStr for FeeDistribution {
+    type Err = Error;
+
+    /// Parses a fixed fee (`10`) or a uniform range (`10..100`), in wei per gas
+    fn from_str(fee: &str) -> Result<Self, Error> {
+        let invalid = || {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("invalid fee '{}', expected <wei> or <min>..<max>", fee),
+            )
         };
+        match fee.split_once("..") {
+            Some((min, max)) => {
+                let (min, max) = (
+                    min.parse().map_err(|_| invalid())?,
+                    max.parse().map_err(|_| invalid())?,
+                );
+                if min > max {
+                    return Err(invalid());
+                }
+                Ok(Self::Uniform { min, max })
+            }
+            None => Ok(Self::Fixed(fee.parse().map_err(|_| invalid())?)),
+        }
+    }
+}
 
-        for group_idx in 0..num_groups {
-            let padding = group_idx < num_transactions % num_groups;
-            let tx_num = num_transactions / num_groups + padding as u128;
-            let sender = LocalWallet::new(&mut self.ethers_rng);
-            let value_per_tx = 50 * ONE_ETHER / tx_num;
-            let mut nonce = 0;
+/// Type and fees of the generated transactions, see [`TransactionGenerator::set_fees`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeeConfig {
+    /// Type of the transactions
+    pub tx_type: TxType,
+    /// `gasPrice` of legacy and access-list transactions, `maxFeePerGas` of dynamic-fee ones
+    pub gas_price: FeeDistribution,
+    /// `maxPriorityFeePerGas` of dynamic-fee transactions, capped to their `maxFeePerGas`
+    pub priority_fee: FeeDistribution,
+}
 
-            self.pre.insert(
-                sender.address().to_hex(),
-                json!({
-                    "balance": DEFAULT_BALANCE_HEX,
-                    "code": "0x",
-                    "nonce": nonce.to_hex(),
-                    "storage": {}
-                }),
-            );
+impl Default for FeeConfig {
+    fn default() -> Self {
+        Self {
+            tx_type: TxType::Legacy,
+            gas_price: FeeDistribution::Fixed(10),
+            priority_fee: FeeDistribution::Fixed(0),
+        }
+    }
+}
 
-            if is_erc20 {
-                add_erc20_balance_prestate(&mut self.pre, erc20_address.unwrap(), sender.address());
-            }
+/// Access lists attached to access-list and dynamic-fee transactions, see
+/// [`TransactionGenerator::set_access_lists`].
+///
+/// The precise access list of a transaction holds every storage key it reads or writes,
+/// with its account: the generator knows the storage layout of the contracts of its
+/// patterns. Accounts touched without storage accesses, such as the recipients of ETH
+/// transfers, are left out: they are either warm anyway or cheaper to access cold.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum AccessListMode {
+    /// Empty access lists
+    #[default]
+    Empty,
+    /// The precise access lists
+    Precise,
+    /// The precise access lists, each storage key kept with probability `coverage`
+    Partial { coverage: f64 },
+    /// The precise access lists with each storage key replaced by a random one, so that
+    /// the declared keys never match the accessed ones
+    Wrong,
+}
 
-            for _ in 0..tx_num {
-                let receiver = LocalWallet::new(&mut self.ethers_rng);
-                self.transactions.push(json!({
-                    "data": if is_erc20 { erc20_transfer(receiver.address(), value_per_tx) } else { "0x".to_string() }, 
-                    "gasLimit": "0x0f4240",
-                    "gasPrice": "0x0a",
-                    "nonce": nonce.to_hex(),
-                    "secretKey": sender.to_hex(),
-                    "sender": sender.address().to_hex(),
-                    "to": if is_erc20 { erc20_address.unwrap().to_hex() } else { receiver.address().to_hex() },
-                    "value": if is_erc20 { "0x00".to_string() } else { value_per_tx.to_hex() }
-                }));
-                nonce += 1;
-            }
+impl FromStr for AccessListMode {
+    type Err = Error;
+
+    /// Parses `empty`, `precise`, `wrong` or `partial[=<coverage>]`; the coverage defaults
+    /// to 0.5
+    fn from_str(mode: &str) -> Result<Self, Error> {
+        let invalid = || {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "invalid access list mode '{}', expected empty, precise, wrong or \
+                    partial[=<coverage>]",
+                    mode
+                ),
+            )
+        };
+        match mode.split_once('=') {
+            Some(("partial", coverage)) => Ok(Self::Partial {
+                coverage: coverage.parse().map_err(|_| invalid())?,
+            }),
+            Some(_) => Err(invalid()),
+            None => match mode {
+                "empty" => Ok(Self::Empty),
+                "precise" => Ok(Self::Precise),
+                "partial" => Ok(Self::Partial { coverage: 0.5 }),
+                "wrong" => Ok(Self::Wrong),
+                _ => Err(invalid()),
+            },
         }
+    }
+}
 
-        Ok(())
+/// Parameters of the `blob` pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobConfig {
+    /// Number of blobs of every transaction, drawn uniformly from this range (at most 6)
+    pub blobs_per_tx: RangeInclusive<u128>,
+    /// `maxFeePerBlobGas` of every transaction
+    pub max_fee_per_blob_gas: FeeDistribution,
+    /// Whether transactions carry their `blobs`, KZG `commitments` and `proofs`, to build
+    /// the network form of blob transactions; a blob takes 256 KiB of hex
+    pub sidecars: bool,
+}
+
+impl Default for BlobConfig {
+    fn default() -> Self {
+        Self {
+            blobs_per_tx: 1..=1,
+            max_fee_per_blob_gas: FeeDistribution::Fixed(1),
+            sidecars: false,
+        }
     }
+}
 
-    fn generate_pattern_m2o(
-        &mut self,
-        num_transactions: u128,
-        num_groups: u128,
-        is_erc20: bool,
-    ) -> Result<(), Error> {
-        self._check_tx_num(num_transactions, num_groups)?;
-        let erc20_address = if is_erc20 {
-            Some(self.deploy_erc20()?)
-        } else {
-            None
-        };
+/// Parameters of the `set-code` pattern.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetCodeConfig {
+    /// Authorizations in the authorization list of every set-code transaction
+    pub authorizations_per_tx: u128,
+    /// Share of the transactions calling into delegated accounts, after the set-code
+    /// transactions
+    pub call_ratio: f64,
+}
 
-        for group_idx in 0..num_groups {
-            let padding = group_idx < num_transactions % num_groups;
-            let tx_num = num_transactions / num_groups + padding as u128;
+impl Default for SetCodeConfig {
+    fn default() -> Self {
+        Self {
+            authorizations_per_tx: 1,
+            call_ratio: 0.5,
+        }
+    }
+}
 
-            let receiver = LocalWallet::new(&mut self.ethers_rng);
+/// Contract deployed by the `deploy` and `create2` patterns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContractTemplate {
+    /// The USDC-like ERC20 token of the ERC20 patterns; the constructor mints a balance to
+    /// the deployer
+    Erc20,
+    /// The ERC-721 contract of the `nft-*` patterns
+    Erc721,
+    /// The ERC-1155 contract of the `erc1155-batch` pattern
+    Erc1155,
+    /// A contract whose code is a single `STOP`, to be padded with
+    /// [`DeployConfig::code_size`]
+    Empty,
+}
 
-            for _ in 0..tx_num {
-                let sender = LocalWallet::new(&mut self.ethers_rng);
-                self.pre.insert(
-                    sender.address().to_hex(),
-                    json!({
-                        "balance": DEFAULT_BALANCE_HEX,
-                        "code": "0x",
-                        "nonce": "0x00",
+impl ContractTemplate {
+    fn runtime(&self) -> &'static str {
+        match self {
+            Self::Erc20 => ERC20_USDC_DEPLOYED_BYTECODE,
+            Self::Erc721 => ERC721_DEPLOYED_BYTECODE,
+            Self::Erc1155 => ERC1155_DEPLOYED_BYTECODE,
+            Self::Empty => "0x00",
+        }
+    }
+
+    /// Returns the storage keys written by the constructor when run by `deployer`
+    fn constructor_keys(&self, deployer: Address) -> Vec<String> {
+        match self {
+            // balances[msg.sender] = supply
+            Self::Erc20 => vec![get_storage_slot_balance(deployer)],
+            Self::Erc721 | Self::Erc1155 | Self::Empty => vec![],
+        }
+    }
+}
+
+impl FromStr for ContractTemplate {
+    type Err = Error;
+
+    fn from_str(template: &str) -> Result<Self, Error> {
+        match template {
+            "erc20" => Ok(Self::Erc20),
+            "erc721" => Ok(Self::Erc721),
+            "erc1155" => Ok(Self::Erc1155),
+            "empty" => Ok(Self::Empty),
+            _ => Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Invalid contract template. Available templates are: 'erc20', 'erc721', \
+                'erc1155', 'empty'.",
+            )),
+        }
+    }
+}
+
+/// Parameters of the `deploy` and `create2` patterns.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeployConfig {
+    /// Contract deployed by every transaction
+    pub template: ContractTemplate,
+    /// Size in bytes the deployed code is padded to with random bytes after the code of the
+    /// template, at most 24576 (EIP-170); `None` deploys the code of the template as is
+    pub code_size: Option<usize>,
+}
+
+impl Default for DeployConfig {
+    fn default() -> Self {
+        Self {
+            template: ContractTemplate::Erc20,
+            code_size: None,
+        }
+    }
+}
+
+/// A pattern of a mixed workload, see [`TransactionGenerator::generate_mixed_transactions`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MixComponent {
+    /// Pattern type, as accepted by [`TransactionGenerator::generate_pattern_transactions`]
+    pub pattern: String,
+    /// Relative share of the transactions generated with this pattern
+    pub weight: f64,
+    /// Whether the pattern generates ERC20 transfers instead of ETH transfers
+    pub is_erc20: bool,
+}
+
+impl FromStr for MixComponent {
+    type Err = Error;
+
+    /// Parses `<pattern>[:erc20][=<weight>]`, e.g. `m2m:erc20=0.3`; the weight defaults to 1
+    fn from_str(component: &str) -> Result<Self, Error> {
+        let invalid = || {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "invalid mix component '{}', expected <pattern>[:erc20][=<weight>]",
+                    component
+                ),
+            )
+        };
+        let (pattern, weight) = match component.split_once('=') {
+            Some((pattern, weight)) => (pattern, weight.parse().map_err(|_| invalid())?),
+            None => (component, 1.0),
+        };
+        let (pattern, is_erc20) = match pattern.split_once(':') {
+            Some((pattern, "erc20")) => (pattern, true),
+            Some(_) => return Err(invalid()),
+            None => (pattern, false),
+        };
+        if pattern.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            pattern: pattern.to_string(),
+            weight,
+            is_erc20,
+        })
+    }
+}
+
+/// Order of the transactions of a mixed workload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interleaving {
+    /// The transactions of each pattern in one block, in the order of the patterns
+    Sequential,
+    /// A random merge of the patterns, keeping the order of the transactions of each pattern
+    /// and the nonce order of every sender
+    Shuffle,
+    /// One transaction of each pattern in turn, until all of them are exhausted
+    RoundRobin,
+}
+
+impl FromStr for Interleaving {
+    type Err = Error;
+
+    fn from_str(interleaving: &str) -> Result<Self, Error> {
+        match interleaving {
+            "sequential" => Ok(Self::Sequential),
+            "shuffle" => Ok(Self::Shuffle),
+            "round-robin" => Ok(Self::RoundRobin),
+            _ => Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Invalid interleaving. Available interleavings are: 'sequential', 'shuffle', \
+                'round-robin'.",
+            )),
+        }
+    }
+}
+
+/// Distribution the accounts of skewed patterns are drawn from, as indices into the pool
+enum AccountDistribution {
+    Zipf {
+        cdf: Vec<f64>,
+    },
+    HotSet {
+        hot: u64,
+        accounts: u64,
+        traffic: f64,
+    },
+}
+
+impl AccountDistribution {
+    fn zipf(accounts: u64, exponent: f64) -> Self {
+        let mut cdf = Vec::with_capacity(accounts as usize);
+        let mut total = 0.0;
+        for rank in 1..=accounts {
+            total += 1.0 / (rank as f64).powf(exponent);
+            cdf.push(total);
+        }
+        cdf.iter_mut().for_each(|p| *p /= total);
+        Self::Zipf { cdf }
+    }
+
+    fn hot_set(accounts: u64, fraction: f64, traffic: f64) -> Self {
+        let hot = ((accounts as f64 * fraction).ceil() as u64).clamp(1, accounts);
+        Self::HotSet {
+            hot,
+            accounts,
+            traffic,
+        }
+    }
+
+    fn sample(&self, rng: &mut StdRng) -> u64 {
+        match self {
+            Self::Zipf { cdf } => {
+                let p = rng.random::<f64>();
+                (cdf.partition_point(|&q| q < p) as u64).min(cdf.len() as u64 - 1)
+            }
+            Self::HotSet {
+                hot,
+                accounts,
+                traffic,
+            } => {
+                if *hot == *accounts || rng.random::<f64>() < *traffic {
+                    rng.random_range(0..*hot)
+                } else {
+                    rng.random_range(*hot..*accounts)
+                }
+            }
+        }
+    }
+}
+
+pub struct TransactionGenerator {
+    ethers_rng: ethers_rand::rngs::StdRng,
+    rng: StdRng,
+    skew: SkewConfig,
+    batch: BatchConfig,
+    deploy: DeployConfig,
+    storage: StorageConfig,
+    fees: FeeConfig,
+    access_lists: AccessListMode,
+    #[cfg_attr(not(feature = "blob"), allow(dead_code))]
+    blob: BlobConfig,
+    set_code: SetCodeConfig,
+    /// Delegate of every account delegated by the `set-code` pattern
+    delegations: HashMap<String, String>,
+    chain_id: u64,
+    deployed_contracts: u128,
+    pre: Map<String, Value>,
+    transactions: Vec<Value>,
+}
+
+impl Default for TransactionGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TransactionGenerator {
+    pub fn new() -> Self {
+        Self::with_seed(rand::random())
+    }
+
+    /// Creates a generator whose wallets, random choices and outputs are fully determined
+    /// by `seed`: the same seed and parameters always produce the same test case (for a
+    /// given version of this crate and its dependencies).
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            ethers_rng: ethers_rand::rngs::StdRng::seed_from_u64(seed),
+            // decorrelated from the wallet keys
+            rng: StdRng::seed_from_u64(seed ^ 0x9e37_79b9_7f4a_7c15),
+            skew: SkewConfig::default(),
+            batch: BatchConfig::default(),
+            deploy: DeployConfig::default(),
+            storage: StorageConfig::default(),
+            fees: FeeConfig::default(),
+            access_lists: AccessListMode::default(),
+            blob: BlobConfig::default(),
+            set_code: SetCodeConfig::default(),
+            delegations: HashMap::new(),
+            chain_id: DEFAULT_CHAIN_ID,
+            deployed_contracts: 0,
+            pre: Map::new(),
+            transactions: Vec::new(),
+        }
+    }
+
+    /// Sets the parameters of the skewed `zipf` and `hot-spot` patterns.
+    pub fn set_skew(&mut self, skew: SkewConfig) {
+        self.skew = skew;
+    }
+
+    /// Sets the parameters of the `erc1155-batch` pattern.
+    pub fn set_batch(&mut self, batch: BatchConfig) {
+        self.batch = batch;
+    }
+
+    /// Sets the parameters of the `deploy` and `create2` patterns.
+    pub fn set_deploy(&mut self, deploy: DeployConfig) {
+        self.deploy = deploy;
+    }
+
+    /// Sets the parameters of the `storage` pattern.
+    pub fn set_storage(&mut self, storage: StorageConfig) {
+        self.storage = storage;
+    }
+
+    /// Sets the type and the fees of the generated transactions, for all patterns.
+    pub fn set_fees(&mut self, fees: FeeConfig) {
+        self.fees = fees;
+    }
+
+    /// Sets the parameters of the `blob` pattern.
+    pub fn set_blobs(&mut self, blob: BlobConfig) {
+        self.blob = blob;
+    }
+
+    /// Sets the parameters of the `set-code` pattern.
+    pub fn set_set_code(&mut self, set_code: SetCodeConfig) {
+        self.set_code = set_code;
+    }
+
+    /// Sets the chain id the EIP-7702 authorizations of the `set-code` pattern are signed
+    /// for, 1 by default; [`sign_transaction`] takes the chain id of the transactions.
+    pub fn set_chain_id(&mut self, chain_id: u64) {
+        self.chain_id = chain_id;
+    }
+
+    /// Sets the access lists of access-list and dynamic-fee transactions, for all patterns.
+    pub fn set_access_lists(&mut self, access_lists: AccessListMode) {
+        self.access_lists = access_lists;
+    }
+
+    fn _check_tx_type_params(&self, pattern_type: &str) -> Result<(), Error> {
+        // blob and set-code transactions are dynamic-fee transactions
+        let typed = matches!(pattern_type, "blob" | "set-code" | "eip7702")
+            || self.fees.tx_type != TxType::Legacy;
+        let message = match self.access_lists {
+            AccessListMode::Empty => return Ok(()),
+            _ if !typed => {
+                "access lists need access-list or dynamic-fee transactions"
+            }
+            AccessListMode::Partial { coverage } if !(0.0..=1.0).contains(&coverage) => {
+                "access list coverage must be between 0.0 and 1.0"
+            }
+            _ => return Ok(()),
+        };
+        Err(Error::new(ErrorKind::InvalidInput, message))
+    }
+
+    /// Returns the accounts touched by a transaction, with the storage keys it accesses;
+    /// `minted` tracks the next token id of the ERC-721 contracts
+    fn precise_access_list(
+        &self,
+        tx: &Value,
+        minted: &mut HashMap<String, u128>,
+    ) -> Vec<(String, Vec<String>)> {
+        let sender = word_address(tx["sender"].as_str().unwrap());
+        let to = tx["to"].as_str().unwrap();
+        let data = &tx["data"].as_str().unwrap()[2..];
+        if to.is_empty() {
+            let created = get_contract_address(sender, from_hex(&tx["nonce"]).unwrap());
+            let keys = self.deploy.template.constructor_keys(sender);
+            return vec![(created.to_hex(), keys)];
+        }
+
+        let Some(account) = self.pre.get(to) else {
+            return vec![(to.to_string(), vec![])];
+        };
+        let contract = word_address(to);
+        let storage = &account["storage"];
+        // delegated accounts run the code of their delegate on their own storage
+        let code_account = match self.delegations.get(to) {
+            Some(delegate) => &self.pre[delegate],
+            None => account,
+        };
+        // the arguments of the call, as 32-byte words after the selector
+        let word = |idx: usize| &data[8 + 64 * idx..8 + 64 * (idx + 1)];
+        let word_u128 = |idx: usize| u128::from_str_radix(&word(idx)[32..], 16).unwrap();
+        let erc721_key =
+            |token_id: u128, slot| get_storage_slot_mapping(&format!("{:x}", token_id), slot);
+        let code = code_account["code"].as_str().unwrap();
+        let keys = match (code, data.get(..8).unwrap_or("")) {
+            (ERC20_USDC_DEPLOYED_BYTECODE, "a9059cbb") => vec![
+                get_storage_slot_balance(sender),
+                get_storage_slot_balance(word_address(word(0))),
+            ],
+            (AMM_PAIR_DEPLOYED_BYTECODE, "2aea6605") => {
+                let (token0, token1) = (
+                    word_address(storage["0x00"].as_str().unwrap()),
+                    word_address(storage["0x01"].as_str().unwrap()),
+                );
+                let (token_in, token_out) = if word_u128(1) == 1 {
+                    (token0, token1)
+                } else {
+                    (token1, token0)
+                };
+                return vec![
+                    (
+                        to.to_string(),
+                        (0..4).map(|slot: u128| slot.to_hex()).collect(),
+                    ),
+                    (
+                        token_in.to_hex(),
+                        vec![
+                            get_storage_slot_balance(sender),
+                            get_storage_slot_balance(contract),
+                            get_storage_slot_allowance(sender, contract),
+                        ],
+                    ),
+                    (
+                        token_out.to_hex(),
+                        vec![
+                            get_storage_slot_balance(contract),
+                            get_storage_slot_balance(sender),
+                        ],
+                    ),
+                ];
+            }
+            (ERC721_DEPLOYED_BYTECODE, "1249c58b") => {
+                let next_id = minted
+                    .entry(to.to_string())
+                    .or_insert_with(|| from_hex(&storage["0x00"]).unwrap_or(0));
+                let token_id = *next_id;
+                *next_id += 1;
+                vec![
+                    "0x00".to_string(),
+                    erc721_key(token_id, 1),
+                    get_storage_slot_mapping(&sender.to_hex()[2..], 2),
+                ]
+            }
+            (ERC721_DEPLOYED_BYTECODE, "23b872dd") => vec![
+                erc721_key(word_u128(2), 1),
+                erc721_key(word_u128(2), 3),
+                get_storage_slot_mapping(&word(0)[24..], 2),
+                get_storage_slot_mapping(&word(1)[24..], 2),
+            ],
+            (ERC721_DEPLOYED_BYTECODE, "095ea7b3") => {
+                vec![erc721_key(word_u128(1), 1), erc721_key(word_u128(1), 3)]
+            }
+            (ERC1155_DEPLOYED_BYTECODE, "2eb2c2d6") => {
+                let (from, to) = (word_address(word(0)), word_address(word(1)));
+                // the ids array follows the 5 head words
+                (0..word_u128(5) as usize)
+                    .map(|idx| word_u128(6 + idx))
+                    .flat_map(|token_id| {
+                        [
+                            get_storage_slot_erc1155_balance(from, token_id),
+                            get_storage_slot_erc1155_balance(to, token_id),
+                        ]
+                    })
+                    .collect()
+            }
+            // the calldata lists the slots, the top bit marking the writes
+            (STORAGE_ACCESS_DEPLOYED_BYTECODE, _) => (0..data.len() / 64)
+                .map(|idx| {
+                    let word = &data[64 * idx..64 * (idx + 1)];
+                    let top = u8::from_str_radix(&word[..1], 16).unwrap() & 7;
+                    format!("0x{:x}{}", top, &word[1..])
+                })
+                .collect(),
+            // the calldata is the salt followed by the init code
+            (CREATE2_FACTORY_DEPLOYED_BYTECODE, _) => {
+                let salt = hex::decode(&data[..64]).unwrap();
+                let init_code = hex::decode(&data[64..]).unwrap();
+                let created = get_create2_address(contract, salt, init_code);
+                return vec![
+                    (to.to_string(), vec![]),
+                    (
+                        created.to_hex(),
+                        self.deploy.template.constructor_keys(contract),
+                    ),
+                ];
+            }
+            _ => vec![],
+        };
+        vec![(to.to_string(), keys)]
+    }
+
+    /// Gives the transactions from `first_tx` on the configured type, access lists and fees,
+    /// funding their senders with the fees on top of their balances in `pre`.
+    fn apply_tx_type(&mut self, first_tx: usize) -> Result<(), Error> {
+        let mut minted = HashMap::new();
+        let access_lists: Vec<Vec<(String, Vec<String>)>> = match self.access_lists {
+            AccessListMode::Empty => vec![vec![]; self.transactions.len() - first_tx],
+            _ => self.transactions[first_tx..]
+                .iter()
+                .map(|tx| self.precise_access_list(tx, &mut minted))
+                .collect(),
+        };
+
+        let mut fees_by_sender: HashMap<String, u128> = HashMap::new();
+        for (tx, access_list) in self.transactions[first_tx..].iter_mut().zip(access_lists) {
+            let tx = tx.as_object_mut().unwrap();
+            let mut access_list_gas = 0;
+            let access_list: Vec<Value> = access_list
+                .into_iter()
+                .map(|(address, keys)| {
+                    let mut unique = HashSet::new();
+                    let keys: Vec<String> = keys
+                        .iter()
+                        .map(|key| access_list_key(key))
+                        .filter(|key| unique.insert(key.clone()))
+                        .filter_map(|key| match self.access_lists {
+                            AccessListMode::Partial { coverage } => {
+                                self.rng.random_bool(coverage).then_some(key)
+                            }
+                            AccessListMode::Wrong => {
+                                Some(format!("0x{}", hex::encode(self.rng.random::<[u8; 32]>())))
+                            }
+                            _ => Some(key),
+                        })
+                        .collect();
+                    (address, keys)
+                })
+                .filter(|(_, keys)| !keys.is_empty())
+                .map(|(address, keys)| {
+                    // EIP-2930 intrinsic gas
+                    access_list_gas += 2400 + 1900 * keys.len() as u128;
+                    json!({ "address": address, "storageKeys": keys })
+                })
+                .collect();
+            let gas_limit = from_hex(&tx["gasLimit"]).unwrap() + access_list_gas;
+            tx.insert("gasLimit".to_string(), gas_limit.to_hex().into());
+
+            let gas_price = self.fees.gas_price.sample(&mut self.rng);
+            // blob and set-code transactions are dynamic-fee transactions
+            let tx_type =
+                if tx.contains_key("blobVersionedHashes") || tx.contains_key("authorizationList") {
+                    TxType::DynamicFee
+                } else {
+                    self.fees.tx_type
+                };
+            match tx_type {
+                TxType::Legacy => {
+                    tx.insert("gasPrice".to_string(), gas_price.to_hex().into());
+                }
+                TxType::AccessList => {
+                    tx.insert("gasPrice".to_string(), gas_price.to_hex().into());
+                    tx.insert("accessList".to_string(), access_list.into());
+                }
+                TxType::DynamicFee => {
+                    let priority_fee = self.fees.priority_fee.sample(&mut self.rng).min(gas_price);
+                    tx.remove("gasPrice");
+                    tx.insert("maxFeePerGas".to_string(), gas_price.to_hex().into());
+                    tx.insert(
+                        "maxPriorityFeePerGas".to_string(),
+                        priority_fee.to_hex().into(),
+                    );
+                    tx.insert("accessList".to_string(), access_list.into());
+                }
+            }
+            let fees = fees_by_sender
+                .entry(tx["sender"].as_str().unwrap().to_string())
+                .or_insert(0);
+            *fees = gas_limit
+                .checked_mul(gas_price)
+                .and_then(|tx_fees| fees.checked_add(tx_fees))
+                .ok_or_else(fees_overflow)?;
+        }
+        for (sender, fees) in fees_by_sender {
+            add_balance_prestate(&mut self.pre, &sender, fees)?;
+        }
+        Ok(())
+    }
+
+    fn _check_tx_num(&self, num_transactions: u128, num_groups: u128) -> Result<(), Error> {
+        if num_transactions < num_groups {
+            Err(Error::new(
+                ErrorKind::InvalidInput,
+                "number of transactions must be greater than number of groups",
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn _check_m2m_params(&self, num_transactions: u128, conflict_rate: f64) -> Result<(), Error> {
+        if num_transactions <= 4 {
+            Err(Error::new(
+                ErrorKind::InvalidInput,
+                "number of transactions must be greater than 4",
+            ))
+        } else if !(0.0..=1.0).contains(&conflict_rate) {
+            Err(Error::new(
+                ErrorKind::InvalidInput,
+                "conflict rate must be between 0.0 and 1.0",
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns the address of the next contract created by the deployer account, so that
+    /// contracts deployed by different patterns never collide
+    fn next_contract_address(&mut self) -> Address {
+        let deployer: Address = CONTRACT_DEPLOYER.parse().unwrap();
+        self.deployed_contracts += 1;
+        get_contract_address(deployer, self.deployed_contracts - 1)
+    }
+
+    fn deploy_erc20(&mut self) -> Result<Address, Error> {
+        let bytecode = ERC20_USDC_DEPLOYED_BYTECODE.to_string();
+        let contract_address = Address::from_slice(&[
+            179, 13, 249, 43, 177, 7, 230, 241, 228, 111, 125, 244, 253, 49, 163, 22, 206, 180,
+            231, 217,
+        ]);
+        // shared by every pattern of a mixed workload, keeping the balances already set up
+        self.pre.entry(contract_address.to_hex()).or_insert(json!({
+            "balance": "0x00",
+            "code": bytecode,
+            "nonce": "0x00",
+            "storage": {}
+        }));
+        Ok(contract_address)
+    }
+
+    fn generate_pattern_o2m(
+        &mut self,
+        num_transactions: u128,
+        num_groups: u128,
+        is_erc20: bool,
+    ) -> Result<(), Error> {
+        self._check_tx_num(num_transactions, num_groups)?;
+        let erc20_address = if is_erc20 {
+            Some(self.deploy_erc20()?)
+        } else {
+            None
+        };
+
+        for group_idx in 0..num_groups {
+            let padding = group_idx < num_transactions % num_groups;
+            let tx_num = num_transactions / num_groups + padding as u128;
+            let sender = LocalWallet::new(&mut self.ethers_rng);
+            let value_per_tx = 50 * ONE_ETHER / tx_num;
+
+            self.pre.insert(
+                sender.address().to_hex(),
+                json!({
+                    "balance": DEFAULT_BALANCE_HEX,
+                    "code": "0x",
+                    "nonce": "0x00",
+                    "storage": {}
+                }),
+            );
+
+            if is_erc20 {
+                add_erc20_balance_prestate(&mut self.pre, erc20_address.unwrap(), sender.address());
+            }
+
+            for nonce in 0..tx_num {
+                let receiver = LocalWallet::new(&mut self.ethers_rng);
+                self.transactions.push(json!({
+                    "data": if is_erc20 { erc20_transfer(receiver.address(), value_per_tx) } else { "0x".to_string() }, 
+                    "gasLimit": "0x0f4240",
+                    "gasPrice": "0x0a",
+                    "nonce": nonce.to_hex(),
+                    "secretKey": sender.to_hex(),
+                    "sender": sender.address().to_hex(),
+                    "to": if is_erc20 { erc20_address.unwrap().to_hex() } else { receiver.address().to_hex() },
+                    "value": if is_erc20 { "0x00".to_string() } else { value_per_tx.to_hex() }
+                }));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn generate_pattern_m2o(
+        &mut self,
+        num_transactions: u128,
+        num_groups: u128,
+        is_erc20: bool,
+    ) -> Result<(), Error> {
+        self._check_tx_num(num_transactions, num_groups)?;
+        let erc20_address = if is_erc20 {
+            Some(self.deploy_erc20()?)
+        } else {
+            None
+        };
+
+        for group_idx in 0..num_groups {
+            let padding = group_idx < num_transactions % num_groups;
+            let tx_num = num_transactions / num_groups + padding as u128;
+
+            let receiver = LocalWallet::new(&mut self.ethers_rng);
+
+            for _ in 0..tx_num {
+                let sender = LocalWallet::new(&mut self.ethers_rng);
+                self.pre.insert(
+                    sender.address().to_hex(),
+                    json!({
+                        "balance": DEFAULT_BALANCE_HEX,
+                        "code": "0x",
+                        "nonce": "0x00",
                         "storage": {}
                     }),
                 );
@@ -410,7 +1622,7 @@ impl TransactionGenerator {
             let receiver_idx = receivers_idxs[i as usize];
             if is_erc20 {
                 self.transactions.push(json!({
-                    "data": erc20_transfer(receivers_wallets[receiver_idx as usize].address(), 1 * ONE_ETHER),
+                    "data": erc20_transfer(receivers_wallets[receiver_idx as usize].address(), ONE_ETHER),
                     "gasLimit": "0x0f4240",
                     "gasPrice": "0x0a",
                     "nonce": senders_nonce[sender_idx as usize].to_hex(),
@@ -468,7 +1680,7 @@ impl TransactionGenerator {
                     account.address(),
                 );
                 self.transactions.push(json!({
-                    "data": erc20_transfer(account.address(), 1 * ONE_ETHER),
+                    "data": erc20_transfer(account.address(), ONE_ETHER),
                     "gasLimit": "0x0f4240",
                     "gasPrice": "0x0a",
                     "nonce": "0x00",
@@ -486,7 +1698,7 @@ impl TransactionGenerator {
                     "secretKey": account.to_hex(),
                     "sender": account.address().to_hex(),
                     "to": account.address().to_hex(),
-                    "value": (1 * ONE_ETHER).to_hex(),
+                    "value": (ONE_ETHER).to_hex(),
                 }));
             }
         }
@@ -494,18 +1706,811 @@ impl TransactionGenerator {
         Ok(())
     }
 
-    pub fn generate_pattern_transactions(
+    fn _check_skew_params(&self, accounts: u128) -> Result<(), Error> {
+        let skew = &self.skew;
+        let message = if accounts < 2 {
+            "number of accounts must be at least 2"
+        } else if !(skew.zipf_exponent >= 0.0 && skew.zipf_exponent.is_finite()) {
+            "zipf exponent must be a non-negative number"
+        } else if !(skew.hot_fraction > 0.0 && skew.hot_fraction <= 1.0) {
+            "hot fraction must be greater than 0.0 and at most 1.0"
+        } else if !(0.0..=1.0).contains(&skew.hot_traffic) {
+            "hot traffic must be between 0.0 and 1.0"
+        } else {
+            return Ok(());
+        };
+        Err(Error::new(ErrorKind::InvalidInput, message))
+    }
+
+    fn generate_pattern_skewed(
         &mut self,
-        pattern_type: &str,
         num_transactions: u128,
-        num_groups: u128,
-        conflict_rate: f64,
+        is_hot_set: bool,
         is_erc20: bool,
     ) -> Result<(), Error> {
-        match pattern_type {
-            "many-to-many" | "m2m" => {
-                self.generate_pattern_m2m(num_transactions, conflict_rate, is_erc20)
-            }
+        let accounts = self.skew.accounts.unwrap_or(num_transactions);
+        self._check_skew_params(accounts)?;
+        let distribution = if is_hot_set {
+            AccountDistribution::hot_set(
+                accounts as u64,
+                self.skew.hot_fraction,
+                self.skew.hot_traffic,
+            )
+        } else {
+            AccountDistribution::zipf(accounts as u64, self.skew.zipf_exponent)
+        };
+        let erc20_address = if is_erc20 {
+            Some(self.deploy_erc20()?)
+        } else {
+            None
+        };
+
+        let mut wallets = Vec::new();
+        for _ in 0..accounts {
+            let account = LocalWallet::new(&mut self.ethers_rng);
+            self.pre.insert(
+                account.address().to_hex(),
+                json!({
+                    "balance": DEFAULT_BALANCE_HEX,
+                    "code": "0x",
+                    "nonce": "0x00",
+                    "storage": {}
+                }),
+            );
+            if is_erc20 {
+                add_erc20_balance_prestate(
+                    &mut self.pre,
+                    erc20_address.unwrap(),
+                    account.address(),
+                );
+            }
+            wallets.push(account);
+        }
+
+        // even the hottest account can send every transaction
+        let value = DEFAULT_BALANCE / (num_transactions + 1);
+        let mut nonces = vec![0u128; accounts as usize];
+        for _ in 0..num_transactions {
+            let sender_idx = distribution.sample(&mut self.rng) as usize;
+            let mut receiver_idx = distribution.sample(&mut self.rng) as usize;
+            for _ in 0..16 {
+                if receiver_idx != sender_idx {
+                    break;
+                }
+                receiver_idx = distribution.sample(&mut self.rng) as usize;
+            }
+            if receiver_idx == sender_idx {
+                // extremely skewed distributions may keep drawing the same account
+                let offset = self.rng.random_range(1..accounts as usize);
+                receiver_idx = (sender_idx + offset) % accounts as usize;
+            }
+            let (sender, receiver) = (&wallets[sender_idx], &wallets[receiver_idx]);
+            self.transactions.push(json!({
+                "data": if is_erc20 { erc20_transfer(receiver.address(), value) } else { "0x".to_string() },
+                "gasLimit": "0x0f4240",
+                "gasPrice": "0x0a",
+                "nonce": nonces[sender_idx].to_hex(),
+                "secretKey": sender.to_hex(),
+                "sender": sender.address().to_hex(),
+                "to": if is_erc20 { erc20_address.unwrap().to_hex() } else { receiver.address().to_hex() },
+                "value": if is_erc20 { "0x00".to_string() } else { value.to_hex() },
+            }));
+            nonces[sender_idx] += 1;
+        }
+
+        Ok(())
+    }
+
+    fn _check_amm_params(&self, traders: u128) -> Result<(), Error> {
+        let message = if self.skew.pairs == 0 {
+            "number of pairs must be at least 1"
+        } else if traders == 0 {
+            "number of accounts must be at least 1"
+        } else if !(self.skew.zipf_exponent >= 0.0 && self.skew.zipf_exponent.is_finite()) {
+            "zipf exponent must be a non-negative number"
+        } else {
+            return Ok(());
+        };
+        Err(Error::new(ErrorKind::InvalidInput, message))
+    }
+
+    /// Deploys the quote token, one base token per pair and the pairs, each holding
+    /// [`AMM_RESERVE`] of both of its tokens. Returns the `(pair, token0, token1)` triples.
+    fn deploy_amm(&mut self, pairs: u128) -> Result<Vec<(Address, Address, Address)>, Error> {
+        let quote_token = self.deploy_erc20()?;
+        let mut deployed = Vec::new();
+        for _ in 0..pairs {
+            let base_token = self.next_contract_address();
+            let pair = self.next_contract_address();
+            self.pre.insert(
+                base_token.to_hex(),
+                json!({
+                    "balance": "0x00",
+                    "code": ERC20_USDC_DEPLOYED_BYTECODE,
+                    "nonce": "0x01",
+                    "storage": {}
+                }),
+            );
+            // tokens are sorted by address, as in Uniswap V2
+            let (token0, token1) = if quote_token < base_token {
+                (quote_token, base_token)
+            } else {
+                (base_token, quote_token)
+            };
+            self.pre.insert(
+                pair.to_hex(),
+                json!({
+                    "balance": "0x00",
+                    "code": AMM_PAIR_DEPLOYED_BYTECODE,
+                    "nonce": "0x01",
+                    "storage": {
+                        "0x00": token0.to_hex(),
+                        "0x01": token1.to_hex(),
+                        "0x02": AMM_RESERVE.to_hex(),
+                        "0x03": AMM_RESERVE.to_hex(),
+                    }
+                }),
+            );
+            for token in [token0, token1] {
+                let slot = get_storage_slot_balance(pair);
+                set_storage_prestate(&mut self.pre, token, slot, &AMM_RESERVE.to_hex());
+            }
+            deployed.push((pair, token0, token1));
+        }
+        Ok(deployed)
+    }
+
+    fn generate_pattern_amm(&mut self, num_transactions: u128) -> Result<(), Error> {
+        let traders = self.skew.accounts.unwrap_or(num_transactions);
+        self._check_amm_params(traders)?;
+        let distribution =
+            AccountDistribution::zipf(self.skew.pairs as u64, self.skew.zipf_exponent);
+        let pairs = self.deploy_amm(self.skew.pairs)?;
+        let wallets = self.new_funded_wallets(traders);
+
+        // token balances and approvals are only set up for the tokens and pairs each
+        // trader actually swaps on
+        let mut funded = HashSet::new();
+        let mut approved = HashSet::new();
+        // even a single trader can sell the same token in every transaction
+        let max_amount_in = DEFAULT_BALANCE / (num_transactions + 1);
+        let mut nonces = vec![0u128; traders as usize];
+        for tx_idx in 0..num_transactions {
+            let trader_idx = (tx_idx % traders) as usize;
+            let trader = &wallets[trader_idx];
+            let (pair, token0, token1) = pairs[distribution.sample(&mut self.rng) as usize];
+            let zero_for_one = self.rng.random::<bool>();
+            let token_in = if zero_for_one { token0 } else { token1 };
+            if funded.insert((trader_idx, token_in)) {
+                add_erc20_balance_prestate(&mut self.pre, token_in, trader.address());
+            }
+            if approved.insert((trader_idx, pair, token_in)) {
+                let slot = get_storage_slot_allowance(trader.address(), pair);
+                set_storage_prestate(&mut self.pre, token_in, slot, MAX_ALLOWANCE_HEX);
+            }
+            let amount_in = self.rng.random_range(max_amount_in / 2..=max_amount_in);
+            self.transactions.push(json!({
+                "data": amm_swap(amount_in, zero_for_one),
+                "gasLimit": "0x0f4240",
+                "gasPrice": "0x0a",
+                "nonce": nonces[trader_idx].to_hex(),
+                "secretKey": trader.to_hex(),
+                "sender": trader.address().to_hex(),
+                "to": pair.to_hex(),
+                "value": "0x00",
+            }));
+            nonces[trader_idx] += 1;
+        }
+
+        Ok(())
+    }
+
+    fn _check_nft_params(&self, accounts: u128, min_accounts: u128) -> Result<(), Error> {
+        if accounts < min_accounts {
+            Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("number of accounts must be at least {}", min_accounts),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Deploys an ERC-721 contract with token `i` already minted to `owners[i]`
+    fn deploy_erc721(&mut self, owners: &[Address]) -> Address {
+        let contract_address = self.next_contract_address();
+        let mut storage = Map::new();
+        storage.insert("0x00".to_string(), (owners.len() as u128).to_hex().into());
+        let mut balances = HashMap::new();
+        for (token_id, owner) in owners.iter().enumerate() {
+            let owner_hex = owner.to_hex();
+            storage.insert(
+                get_storage_slot_mapping(&format!("{:x}", token_id), 1),
+                owner_hex.clone().into(),
+            );
+            *balances.entry(owner_hex).or_insert(0u128) += 1;
+        }
+        for (owner, balance) in balances {
+            storage.insert(
+                get_storage_slot_mapping(owner.strip_prefix("0x").unwrap(), 2),
+                balance.to_hex().into(),
+            );
+        }
+        self.pre.insert(
+            contract_address.to_hex(),
+            json!({
+                "balance": "0x00",
+                "code": ERC721_DEPLOYED_BYTECODE,
+                "nonce": "0x01",
+                "storage": storage
+            }),
+        );
+        contract_address
+    }
+
+    fn new_funded_wallets(&mut self, accounts: u128) -> Vec<Wallet<SigningKey>> {
+        let mut wallets = Vec::new();
+        for _ in 0..accounts {
+            let account = LocalWallet::new(&mut self.ethers_rng);
+            self.pre.insert(
+                account.address().to_hex(),
+                json!({
+                    "balance": DEFAULT_BALANCE_HEX,
+                    "code": "0x",
+                    "nonce": "0x00",
+                    "storage": {}
+                }),
+            );
+            wallets.push(account);
+        }
+        wallets
+    }
+
+    /// Every transaction mints a token, so that all of them contend on the token counter
+    fn generate_pattern_nft_mint(&mut self, num_transactions: u128) -> Result<(), Error> {
+        let accounts = self.skew.accounts.unwrap_or(num_transactions);
+        self._check_nft_params(accounts, 1)?;
+        let nft_address = self.deploy_erc721(&[]);
+        let wallets = self.new_funded_wallets(accounts);
+
+        let mut nonces = vec![0u128; accounts as usize];
+        for tx_idx in 0..num_transactions {
+            let minter_idx = (tx_idx % accounts) as usize;
+            let minter = &wallets[minter_idx];
+            self.transactions.push(json!({
+                "data": "0x1249c58b",
+                "gasLimit": "0x0f4240",
+                "gasPrice": "0x0a",
+                "nonce": nonces[minter_idx].to_hex(),
+                "secretKey": minter.to_hex(),
+                "sender": minter.address().to_hex(),
+                "to": nft_address.to_hex(),
+                "value": "0x00",
+            }));
+            nonces[minter_idx] += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Every account starts with one token. Each transaction transfers a random token
+    /// from its current owner to another random account, or, with `is_approval`, every
+    /// other transaction approves another random account for a random token, which that
+    /// account then transfers to itself.
+    fn generate_pattern_nft_transfer(
+        &mut self,
+        num_transactions: u128,
+        is_approval: bool,
+    ) -> Result<(), Error> {
+        let accounts = self.skew.accounts.unwrap_or(num_transactions);
+        self._check_nft_params(accounts, 2)?;
+        let wallets = self.new_funded_wallets(accounts);
+        let owners: Vec<Address> = wallets.iter().map(|wallet| wallet.address()).collect();
+        let nft_address = self.deploy_erc721(&owners);
+
+        let mut owner_of: Vec<usize> = (0..accounts as usize).collect();
+        let mut nonces = vec![0u128; accounts as usize];
+        let mut tx_idx = 0;
+        while tx_idx < num_transactions {
+            let token_id = self.rng.random_range(0..accounts as usize);
+            let owner_idx = owner_of[token_id];
+            let offset = self.rng.random_range(1..accounts as usize);
+            let receiver_idx = (owner_idx + offset) % accounts as usize;
+            let (owner, receiver) = (&wallets[owner_idx], &wallets[receiver_idx]);
+            let mut calls = vec![];
+            if is_approval {
+                calls.push((
+                    owner_idx,
+                    erc721_approve(receiver.address(), token_id as u128),
+                ));
+            }
+            let sender_idx = if is_approval { receiver_idx } else { owner_idx };
+            calls.push((
+                sender_idx,
+                erc721_transfer_from(owner.address(), receiver.address(), token_id as u128),
+            ));
+            for (sender_idx, data) in calls {
+                if tx_idx == num_transactions {
+                    break;
+                }
+                let sender = &wallets[sender_idx];
+                self.transactions.push(json!({
+                    "data": data,
+                    "gasLimit": "0x0f4240",
+                    "gasPrice": "0x0a",
+                    "nonce": nonces[sender_idx].to_hex(),
+                    "secretKey": sender.to_hex(),
+                    "sender": sender.address().to_hex(),
+                    "to": nft_address.to_hex(),
+                    "value": "0x00",
+                }));
+                nonces[sender_idx] += 1;
+                tx_idx += 1;
+            }
+            owner_of[token_id] = receiver_idx;
+        }
+
+        Ok(())
+    }
+
+    fn _check_batch_params(&self, accounts: u128) -> Result<(), Error> {
+        let message = if accounts < 2 {
+            "number of accounts must be at least 2"
+        } else if self.batch.batch_size == 0 {
+            "batch size must be at least 1"
+        } else if !(0.0..=1.0).contains(&self.batch.id_overlap) {
+            "token id overlap must be between 0.0 and 1.0"
+        } else {
+            return Ok(());
+        };
+        Err(Error::new(ErrorKind::InvalidInput, message))
+    }
+
+    /// Every transaction transfers a batch of token ids from a random account of the pool
+    /// to another, with senders funded for the ids they send
+    fn generate_pattern_erc1155_batch(&mut self, num_transactions: u128) -> Result<(), Error> {
+        let accounts = self.skew.accounts.unwrap_or(num_transactions);
+        self._check_batch_params(accounts)?;
+        let batch_size = self.batch.batch_size;
+        let shared_ids =
+            ((batch_size as f64 * self.batch.id_overlap).round() as u128).min(batch_size);
+
+        let erc1155_address = self.next_contract_address();
+        self.pre.insert(
+            erc1155_address.to_hex(),
+            json!({
+                "balance": "0x00",
+                "code": ERC1155_DEPLOYED_BYTECODE,
+                "nonce": "0x01",
+                "storage": {}
+            }),
+        );
+        let wallets = self.new_funded_wallets(accounts);
+
+        // a new balance slot costs about 22k gas per token id
+        let gas_limit = 1_000_000 + 30_000 * batch_size;
+        // ids below `batch_size` are shared, the others are unique to a transaction
+        let mut next_unique_id = batch_size;
+        let mut funded = HashSet::new();
+        let mut nonces = vec![0u128; accounts as usize];
+        for tx_idx in 0..num_transactions {
+            let sender_idx = (tx_idx % accounts) as usize;
+            let offset = self.rng.random_range(1..accounts as usize);
+            let receiver_idx = (sender_idx + offset) % accounts as usize;
+            let (sender, receiver) = (&wallets[sender_idx], &wallets[receiver_idx]);
+
+            let mut token_ids: Vec<u128> = (0..batch_size).collect();
+            for i in 0..shared_ids as usize {
+                let j = self.rng.random_range(i..batch_size as usize);
+                token_ids.swap(i, j);
+            }
+            token_ids.truncate(shared_ids as usize);
+            token_ids.extend(next_unique_id..next_unique_id + batch_size - shared_ids);
+            next_unique_id += batch_size - shared_ids;
+            let values: Vec<u128> = token_ids
+                .iter()
+                .map(|_| self.rng.random_range(1..=100))
+                .collect();
+            for &token_id in &token_ids {
+                if funded.insert((sender_idx, token_id)) {
+                    add_erc1155_balance_prestate(
+                        &mut self.pre,
+                        erc1155_address,
+                        sender.address(),
+                        token_id,
+                    );
+                }
+            }
+
+            self.transactions.push(json!({
+                "data": erc1155_safe_batch_transfer_from(
+                    sender.address(),
+                    receiver.address(),
+                    &token_ids,
+                    &values
+                ),
+                "gasLimit": gas_limit.to_hex(),
+                "gasPrice": "0x0a",
+                "nonce": nonces[sender_idx].to_hex(),
+                "secretKey": sender.to_hex(),
+                "sender": sender.address().to_hex(),
+                "to": erc1155_address.to_hex(),
+                "value": "0x00",
+            }));
+            nonces[sender_idx] += 1;
+        }
+
+        Ok(())
+    }
+
+    fn _check_deploy_params(&self, accounts: u128) -> Result<(), Error> {
+        if accounts == 0 {
+            Err(Error::new(
+                ErrorKind::InvalidInput,
+                "number of accounts must be at least 1",
+            ))
+        } else if self
+            .deploy
+            .code_size
+            .is_some_and(|size| size > MAX_CODE_SIZE)
+        {
+            Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("code size must be at most {} bytes", MAX_CODE_SIZE),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns the init code of a new instance of the configured template, its runtime code
+    /// padded with random bytes to the configured size
+    fn template_init_code(&mut self) -> String {
+        let template = self.deploy.template;
+        let mut runtime = template.runtime().strip_prefix("0x").unwrap().to_string();
+        let padding = self
+            .deploy
+            .code_size
+            .unwrap_or(0)
+            .saturating_sub(runtime.len() / 2);
+        let mut bytes = vec![0u8; padding];
+        self.rng.fill(&mut bytes[..]);
+        runtime += &hex::encode(bytes);
+        let constructor = match template {
+            // balances[msg.sender] = DEFAULT_BALANCE
+            ContractTemplate::Erc20 => {
+                format!("7f{:064x}336000526000602052604060002055", DEFAULT_BALANCE)
+            }
+            _ => String::new(),
+        };
+        init_code(&constructor, &runtime)
+    }
+
+    /// Every transaction deploys a new instance of the configured template, with a
+    /// contract-creation transaction or, with `is_create2`, through the CREATE2 factory
+    fn generate_pattern_deploy(
+        &mut self,
+        num_transactions: u128,
+        is_create2: bool,
+    ) -> Result<(), Error> {
+        let accounts = self.skew.accounts.unwrap_or(num_transactions);
+        self._check_deploy_params(accounts)?;
+        if is_create2 {
+            self.pre
+                .entry(CREATE2_FACTORY_ADDRESS.to_string())
+                .or_insert(json!({
+                    "balance": "0x00",
+                    "code": CREATE2_FACTORY_DEPLOYED_BYTECODE,
+                    "nonce": "0x01",
+                    "storage": {}
+                }));
+        }
+        let wallets = self.new_funded_wallets(accounts);
+
+        let mut nonces = vec![0u128; accounts as usize];
+        for tx_idx in 0..num_transactions {
+            let deployer_idx = (tx_idx % accounts) as usize;
+            let deployer = &wallets[deployer_idx];
+            let init_code = self.template_init_code();
+            let (to, data) = if is_create2 {
+                let salt: [u8; 32] = self.rng.random();
+                let calldata = format!("0x{}{}", hex::encode(salt), &init_code[2..]);
+                (CREATE2_FACTORY_ADDRESS.to_string(), calldata)
+            } else {
+                (String::new(), init_code)
+            };
+            // code deposit (200 gas per byte) and calldata dominate the cost
+            let gas_limit = 1_000_000 + 250 * (data.len() as u128 - 2) / 2;
+            self.transactions.push(json!({
+                "data": data,
+                "gasLimit": gas_limit.to_hex(),
+                "gasPrice": "0x0a",
+                "nonce": nonces[deployer_idx].to_hex(),
+                "secretKey": deployer.to_hex(),
+                "sender": deployer.address().to_hex(),
+                "to": to,
+                "value": "0x00",
+            }));
+            nonces[deployer_idx] += 1;
+        }
+
+        Ok(())
+    }
+
+    fn _check_storage_params(&self, accounts: u128) -> Result<(), Error> {
+        let storage = &self.storage;
+        let message = if accounts == 0 {
+            "number of accounts must be at least 1"
+        } else if storage.slots_per_tx == 0 {
+            "number of slots per transaction must be at least 1"
+        } else if !(0.0..=1.0).contains(&storage.write_ratio) {
+            "write ratio must be between 0.0 and 1.0"
+        } else if !(0.0..=1.0).contains(&storage.key_overlap) {
+            "key overlap must be between 0.0 and 1.0"
+        } else {
+            return Ok(());
+        };
+        Err(Error::new(ErrorKind::InvalidInput, message))
+    }
+
+    /// Every transaction reads and increments storage slots of the storage access contract,
+    /// all of which hold a non-zero value in `pre`
+    fn generate_pattern_storage(&mut self, num_transactions: u128) -> Result<(), Error> {
+        let accounts = self.skew.accounts.unwrap_or(num_transactions);
+        self._check_storage_params(accounts)?;
+        let slots_per_tx = self.storage.slots_per_tx;
+        let round = |fraction: f64| {
+            ((slots_per_tx as f64 * fraction).round() as u128).min(slots_per_tx) as usize
+        };
+        let shared_slots = round(self.storage.key_overlap);
+        let written_slots = round(self.storage.write_ratio);
+
+        let contract_address = self.next_contract_address();
+        let mut storage = Map::new();
+        let wallets = self.new_funded_wallets(accounts);
+
+        // keys below `shared_slots` are shared, the others are unique to a transaction
+        let mut next_unique_key = shared_slots as u128;
+        let mut nonces = vec![0u128; accounts as usize];
+        for tx_idx in 0..num_transactions {
+            let sender_idx = (tx_idx % accounts) as usize;
+            let sender = &wallets[sender_idx];
+
+            let mut keys: Vec<u128> = (0..shared_slots as u128).collect();
+            keys.extend(next_unique_key..next_unique_key + slots_per_tx - shared_slots as u128);
+            next_unique_key += slots_per_tx - shared_slots as u128;
+            // shuffle so that the written slots are a random subset
+            for i in 0..keys.len() {
+                let j = self.rng.random_range(i..keys.len());
+                keys.swap(i, j);
+            }
+
+            let mut data = "0x".to_string();
+            for (i, &key) in keys.iter().enumerate() {
+                let mut slot = storage_access_slot(key);
+                storage.insert("0x".to_owned() + &hex::encode(slot), Value::from("0x01"));
+                if i < written_slots {
+                    slot[0] |= 0x80;
+                }
+                data += &hex::encode(slot);
+            }
+
+            self.transactions.push(json!({
+                "data": data,
+                // a cold read costs 2100 gas and an update about 5000 more
+                "gasLimit": (1_000_000 + 10_000 * slots_per_tx).to_hex(),
+                "gasPrice": "0x0a",
+                "nonce": nonces[sender_idx].to_hex(),
+                "secretKey": sender.to_hex(),
+                "sender": sender.address().to_hex(),
+                "to": contract_address.to_hex(),
+                "value": "0x00",
+            }));
+            nonces[sender_idx] += 1;
+        }
+
+        self.pre.insert(
+            contract_address.to_hex(),
+            json!({
+                "balance": "0x00",
+                "code": STORAGE_ACCESS_DEPLOYED_BYTECODE,
+                "nonce": "0x01",
+                "storage": storage
+            }),
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "blob")]
+    fn _check_blob_params(&self, accounts: u128) -> Result<(), Error> {
+        let blobs_per_tx = &self.blob.blobs_per_tx;
+        let message = if accounts == 0 {
+            "number of accounts must be at least 1"
+        } else if blobs_per_tx.is_empty()
+            || *blobs_per_tx.start() == 0
+            || *blobs_per_tx.end() > MAX_BLOBS_PER_TX
+        {
+            "number of blobs per transaction must be between 1 and 6"
+        } else {
+            return Ok(());
+        };
+        Err(Error::new(ErrorKind::InvalidInput, message))
+    }
+
+    /// Every transaction is an EIP-4844 transaction carrying random blobs to the blob inbox;
+    /// the senders are funded with the blob fees on top of their balance. The execution fees
+    /// and the access lists are those of every dynamic-fee transaction, set afterwards
+    #[cfg(feature = "blob")]
+    fn generate_pattern_blob(&mut self, num_transactions: u128) -> Result<(), Error> {
+        let accounts = self.skew.accounts.unwrap_or(num_transactions);
+        self._check_blob_params(accounts)?;
+        let wallets = self.new_funded_wallets(accounts);
+
+        let mut nonces = vec![0u128; accounts as usize];
+        let mut blob_fees = vec![0u128; accounts as usize];
+        for tx_idx in 0..num_transactions {
+            let sender_idx = (tx_idx % accounts) as usize;
+            let sender = &wallets[sender_idx];
+            let blob_count = self.rng.random_range(self.blob.blobs_per_tx.clone());
+            let max_fee_per_blob_gas = self.blob.max_fee_per_blob_gas.sample(&mut self.rng);
+            let blobs: Vec<[String; 4]> = (0..blob_count)
+                .map(|_| random_blob(&mut self.rng))
+                .collect();
+            let field = |idx: usize| -> Vec<String> {
+                blobs.iter().map(|blob| blob[idx].clone()).collect()
+            };
+            let mut tx = json!({
+                "blobVersionedHashes": field(3),
+                "data": "0x",
+                "gasLimit": "0x0f4240",
+                "maxFeePerBlobGas": max_fee_per_blob_gas.to_hex(),
+                "nonce": nonces[sender_idx].to_hex(),
+                "secretKey": sender.to_hex(),
+                "sender": sender.address().to_hex(),
+                "to": BLOB_INBOX,
+                "value": "0x00",
+            });
+            if self.blob.sidecars {
+                tx["blobs"] = field(0).into();
+                tx["commitments"] = field(1).into();
+                tx["proofs"] = field(2).into();
+            }
+            self.transactions.push(tx);
+            nonces[sender_idx] += 1;
+            blob_fees[sender_idx] = (GAS_PER_BLOB * blob_count)
+                .checked_mul(max_fee_per_blob_gas)
+                .and_then(|fees| blob_fees[sender_idx].checked_add(fees))
+                .ok_or_else(fees_overflow)?;
+        }
+
+        for (wallet, fees) in wallets.iter().zip(blob_fees) {
+            add_balance_prestate(&mut self.pre, &wallet.address().to_hex(), fees)?;
+        }
+        Ok(())
+    }
+
+    fn _check_set_code_params(&self, calls: u128, num_transactions: u128) -> Result<(), Error> {
+        let message = if self.set_code.authorizations_per_tx == 0 {
+            "number of authorizations per transaction must be at least 1"
+        } else if self.skew.accounts == Some(0) {
+            "number of accounts must be at least 1"
+        } else if !(0.0..=1.0).contains(&self.set_code.call_ratio) {
+            "call ratio must be between 0.0 and 1.0"
+        } else if calls >= num_transactions {
+            "at least one set-code transaction is needed"
+        } else {
+            return Ok(());
+        };
+        Err(Error::new(ErrorKind::InvalidInput, message))
+    }
+
+    /// EIP-7702 transactions whose authorization lists delegate accounts to a storage
+    /// contract, each calling into the first account it delegates, followed by calls into
+    /// random delegated accounts. Every call increments a counter in the storage of the
+    /// delegated account. Authorities are drawn from a pool of `accounts` wallets, so that
+    /// several authorizations may touch the same account; by default every authorization
+    /// delegates a new one.
+    fn generate_pattern_set_code(&mut self, num_transactions: u128) -> Result<(), Error> {
+        let calls = (num_transactions as f64 * self.set_code.call_ratio).round() as u128;
+        self._check_set_code_params(calls, num_transactions)?;
+        let authorizations_per_tx = self.set_code.authorizations_per_tx;
+        let set_code_txs = num_transactions - calls;
+        let authorities = match self.skew.accounts {
+            Some(accounts) => self.new_funded_wallets(accounts),
+            None => self.new_funded_wallets(set_code_txs * authorizations_per_tx),
+        };
+        let senders = self.new_funded_wallets(num_transactions);
+
+        let delegate = self.next_contract_address();
+        self.pre.insert(
+            delegate.to_hex(),
+            json!({
+                "balance": "0x00",
+                "code": STORAGE_ACCESS_DEPLOYED_BYTECODE,
+                "nonce": "0x01",
+                "storage": {}
+            }),
+        );
+        // writes the slot 0 of the account running the storage contract
+        let mut counter_slot = [0u8; 32];
+        counter_slot[0] = 0x80;
+        let data = format!("0x{}", hex::encode(counter_slot));
+
+        let mut nonces = vec![0u64; authorities.len()];
+        let mut delegated = Vec::new();
+        for tx_idx in 0..set_code_txs {
+            let authority_indices: Vec<usize> = (0..authorizations_per_tx)
+                .map(|auth_idx| match self.skew.accounts {
+                    Some(accounts) => self.rng.random_range(0..accounts as usize),
+                    None => (tx_idx * authorizations_per_tx + auth_idx) as usize,
+                })
+                .collect();
+            let authorization_list: Vec<Value> = authority_indices
+                .iter()
+                .map(|&idx| {
+                    // a valid authorization bumps the nonce of its authority
+                    let nonce = nonces[idx];
+                    nonces[idx] += 1;
+                    sign_authorization(&authorities[idx], self.chain_id, delegate, nonce)
+                })
+                .collect();
+            for &idx in &authority_indices {
+                if !delegated.contains(&idx) {
+                    delegated.push(idx);
+                }
+            }
+
+            let sender = &senders[tx_idx as usize];
+            self.transactions.push(json!({
+                "accessList": [],
+                "authorizationList": authorization_list,
+                "data": data,
+                "gasLimit": (1_000_000 + GAS_PER_AUTHORIZATION * authorizations_per_tx).to_hex(),
+                "maxFeePerGas": "0x0a",
+                "maxPriorityFeePerGas": "0x00",
+                "nonce": "0x00",
+                "secretKey": sender.to_hex(),
+                "sender": sender.address().to_hex(),
+                "to": authorities[authority_indices[0]].address().to_hex(),
+                "value": "0x00",
+            }));
+        }
+
+        for sender in &senders[set_code_txs as usize..] {
+            let authority = &authorities[delegated[self.rng.random_range(0..delegated.len())]];
+            self.transactions.push(json!({
+                "data": data,
+                "gasLimit": "0x0f4240",
+                "gasPrice": "0x0a",
+                "nonce": "0x00",
+                "secretKey": sender.to_hex(),
+                "sender": sender.address().to_hex(),
+                "to": authority.address().to_hex(),
+                "value": "0x00",
+            }));
+        }
+
+        for &idx in &delegated {
+            self.delegations
+                .insert(authorities[idx].address().to_hex(), delegate.to_hex());
+        }
+        Ok(())
+    }
+
+    pub fn generate_pattern_transactions(
+        &mut self,
+        pattern_type: &str,
+        num_transactions: u128,
+        num_groups: u128,
+        conflict_rate: f64,
+        is_erc20: bool,
+    ) -> Result<(), Error> {
+        self._check_tx_type_params(pattern_type)?;
+        let first_tx = self.transactions.len();
+        match pattern_type {
+            "many-to-many" | "m2m" => {
+                self.generate_pattern_m2m(num_transactions, conflict_rate, is_erc20)
+            }
             "ring" | "chained" | "chain" => {
                 self.generate_pattern_chained(num_transactions, num_groups, is_erc20)
             }
@@ -518,15 +2523,154 @@ impl TransactionGenerator {
             "self-to-self" | "s2s" => {
                 self.generate_pattern_s2s(num_transactions, is_erc20)
             }
+            "zipf" => self.generate_pattern_skewed(num_transactions, false, is_erc20),
+            "hot-spot" | "hotspot" => {
+                self.generate_pattern_skewed(num_transactions, true, is_erc20)
+            }
+            "amm" | "swap" => self.generate_pattern_amm(num_transactions),
+            "nft-mint" => self.generate_pattern_nft_mint(num_transactions),
+            "nft-transfer" => self.generate_pattern_nft_transfer(num_transactions, false),
+            "nft-approve" => self.generate_pattern_nft_transfer(num_transactions, true),
+            "erc1155-batch" => self.generate_pattern_erc1155_batch(num_transactions),
+            "deploy" | "create" => self.generate_pattern_deploy(num_transactions, false),
+            "create2" => self.generate_pattern_deploy(num_transactions, true),
+            "storage" => self.generate_pattern_storage(num_transactions),
+            "set-code" | "eip7702" => self.generate_pattern_set_code(num_transactions),
+            #[cfg(feature = "blob")]
+            "blob" => self.generate_pattern_blob(num_transactions),
+            #[cfg(not(feature = "blob"))]
+            "blob" => Err(Error::new(
+                ErrorKind::InvalidInput,
+                "the blob pattern needs the `blob` feature",
+            )),
             _ => Err(Error::new(
                 ErrorKind::InvalidInput,
-                format!(
-                    "Invalid pattern type. Available patterns are: 'many-to-many' (or 'm2m'), \
-                    'chained' (or 'ring', 'chain'), 'one-to-many' (or 'o2m'), 'many-to-one' (or 'm2o'), \
-                    'self-to-self' (or 's2s')."
-                ),
+                "Invalid pattern type. Available patterns are: 'many-to-many' (or 'm2m'), \
+                'chained' (or 'ring', 'chain'), 'one-to-many' (or 'o2m'), 'many-to-one' (or 'm2o'), \
+                'self-to-self' (or 's2s'), 'zipf', 'hot-spot' (or 'hotspot'), 'amm' (or 'swap'), \
+                'nft-mint', 'nft-transfer', 'nft-approve', 'erc1155-batch', 'deploy' (or 'create'), \
+                'create2', 'storage', 'blob', 'set-code' (or 'eip7702').",
             )),
+        }?;
+        self.apply_tx_type(first_tx)
+    }
+
+    /// Generates a mixed workload composed of several patterns sharing a single `pre`
+    /// state, and orders its transactions with the given interleaving.
+    ///
+    /// The transactions are split among the components in proportion to their weights; a
+    /// component whose share rounds to zero transactions is skipped. `num_groups` and
+    /// `conflict_rate` apply to every component, as in
+    /// [`generate_pattern_transactions`](Self::generate_pattern_transactions).
+    pub fn generate_mixed_transactions(
+        &mut self,
+        components: &[MixComponent],
+        num_transactions: u128,
+        num_groups: u128,
+        conflict_rate: f64,
+        interleaving: Interleaving,
+    ) -> Result<(), Error> {
+        let total_weight: f64 = components.iter().map(|component| component.weight).sum();
+        if components.is_empty()
+            || !components.iter().all(|component| component.weight >= 0.0)
+            || !(total_weight > 0.0 && total_weight.is_finite())
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "a mix needs at least one component, with non-negative weights of positive sum",
+            ));
+        }
+
+        // largest remainder apportionment of the transactions
+        let shares: Vec<f64> = components
+            .iter()
+            .map(|component| num_transactions as f64 * component.weight / total_weight)
+            .collect();
+        let mut counts: Vec<u128> = shares.iter().map(|share| share.floor() as u128).collect();
+        let mut by_remainder: Vec<usize> = (0..components.len()).collect();
+        by_remainder.sort_by(|&a, &b| {
+            (shares[b] - shares[b].floor()).total_cmp(&(shares[a] - shares[a].floor()))
+        });
+        let assigned: u128 = counts.iter().sum();
+        for &idx in by_remainder
+            .iter()
+            .take(num_transactions.saturating_sub(assigned) as usize)
+        {
+            counts[idx] += 1;
+        }
+
+        let first_tx = self.transactions.len();
+        let mut blocks = vec![];
+        for (component, count) in components.iter().zip(counts) {
+            if count == 0 {
+                continue;
+            }
+            let start = self.transactions.len();
+            self.generate_pattern_transactions(
+                &component.pattern,
+                count,
+                num_groups,
+                conflict_rate,
+                component.is_erc20,
+            )?;
+            blocks.push(self.transactions[start..].to_vec());
+            self.transactions.truncate(start);
         }
+
+        let mixed = match interleaving {
+            Interleaving::Sequential => blocks.concat(),
+            Interleaving::RoundRobin => {
+                let mut iters: Vec<_> = blocks.into_iter().map(Vec::into_iter).collect();
+                let mut mixed = vec![];
+                loop {
+                    let before = mixed.len();
+                    mixed.extend(iters.iter_mut().filter_map(Iterator::next));
+                    if mixed.len() == before {
+                        break mixed;
+                    }
+                }
+            }
+            Interleaving::Shuffle => {
+                // a random merge keeps the order of each pattern, whose transactions may depend
+                // on earlier ones of other senders (e.g. an NFT changing hands)
+                let mut picks: Vec<usize> = blocks
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(block, txs)| std::iter::repeat_n(block, txs.len()))
+                    .collect();
+                picks.shuffle(&mut self.rng);
+                let mut next_in_block: Vec<usize> = blocks
+                    .iter()
+                    .scan(0, |start, txs| {
+                        *start += txs.len();
+                        Some(*start - txs.len())
+                    })
+                    .collect();
+                let transactions = blocks.concat();
+                // a sender shared by several patterns still gets its transactions in nonce
+                // order, on the positions they were merged to
+                let mut positions: HashMap<&Value, Vec<usize>> = HashMap::new();
+                for (position, block) in picks.into_iter().enumerate() {
+                    positions
+                        .entry(&transactions[next_in_block[block]]["sender"])
+                        .or_default()
+                        .push(position);
+                    next_in_block[block] += 1;
+                }
+                let mut mixed = vec![Value::Null; transactions.len()];
+                let mut next: HashMap<&Value, usize> = HashMap::new();
+                for tx in &transactions {
+                    let sender = &tx["sender"];
+                    let idx = next.entry(sender).or_insert(0);
+                    mixed[positions[sender][*idx]] = tx.clone();
+                    *idx += 1;
+                }
+                mixed
+            }
+        };
+        self.transactions.truncate(first_tx);
+        self.transactions.extend(mixed);
+        Ok(())
     }
 
     pub fn get_data(&self) -> (Map<String, Value>, Vec<Value>) {
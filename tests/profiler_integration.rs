@@ -18,3 +18,330 @@ fn test_profiler() {
 
     profiler::dump_json("./tests/output.json");
 }
+
+#[cfg(unix)]
+#[test]
+fn test_cpu_time() {
+    profiler::enable_cpu_time(true);
+    profiler::start("cpu-busy");
+    let mut acc = 0u64;
+    for i in 0..20_000_000u64 {
+        acc = acc.wrapping_mul(31).wrapping_add(i);
+    }
+    std::hint::black_box(acc);
+    profiler::end("cpu-busy");
+    profiler::start("cpu-idle");
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    profiler::end("cpu-idle");
+    profiler::enable_cpu_time(false);
+
+    let output: serde_json::Value = serde_json::from_str(&profiler::dump()).unwrap();
+    let events: Vec<&serde_json::Value> = output
+        .as_array()
+        .unwrap()
+        .iter()
+        .flat_map(|thread| thread.as_array().unwrap())
+        .collect();
+    let find = |name: &str| *events.iter().find(|event| event["name"] == name).unwrap();
+    let (busy, idle) = (find("cpu-busy"), find("cpu-idle"));
+    assert!(busy["cpu_runtime"].as_u64().unwrap() > 0);
+    assert!(idle["cpu_runtime"].as_u64().unwrap() < idle["runtime"].as_u64().unwrap() / 2);
+}
+
+#[test]
+fn test_summary() {
+    for _ in 0..10 {
+        profiler::start_multi("summary-task");
+        profiler::end_multi("summary-task");
+    }
+
+    let summary: serde_json::Value = serde_json::from_str(&profiler::summary()).unwrap();
+    let task = summary["tasks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|task| task["name"] == "summary-task")
+        .unwrap();
+    assert_eq!(task["count"], 10);
+    assert_eq!(task["open"], 0);
+    assert!(task["min"].as_u64() <= task["p50"].as_u64());
+    assert!(task["p50"].as_u64() <= task["max"].as_u64());
+}
+
+#[test]
+fn test_html_report() {
+    profiler::start("html-task");
+    profiler::note_str("html-task", "payload", "</script><b>escaped</b>");
+    profiler::end("html-task");
+
+    let dump: serde_json::Value = serde_json::from_str(&profiler::dump()).unwrap();
+    let html = profiler::to_html(&dump, "<Report>");
+    assert!(html.contains("<title>&lt;Report&gt;</title>"));
+    assert!(html.contains("html-task"));
+    assert_eq!(html.matches("</script>").count(), 2);
+}
+
+#[test]
+fn test_analysis() {
+    let event = |event_type: &str, tx: &str, start: u64, end: u64| {
+        serde_json::json!({
+            "type": event_type,
+            "tx": tx,
+            "status": "success",
+            "runtime": end - start,
+            "start": start,
+            "end": end,
+            "detail": {},
+        })
+    };
+    let dump = serde_json::json!([
+        [
+            event("transaction", "0x0a", 0, 100),
+            event("transaction", "0x0a", 100, 150),
+            event("commit", "0x0a", 160, 170),
+        ],
+        [
+            event("transaction", "0x0b", 0, 80),
+            event("commit", "0x0b", 170, 180),
+        ],
+    ]);
+
+    let report = profiler::analyze(&dump);
+    assert_eq!(report["makespan"], 180);
+    assert_eq!(report["transactions"], 2);
+    assert_eq!(report["executions"], 3);
+    assert_eq!(report["re_executions"], 1);
+    assert_eq!(report["per_transaction"]["0x0a"]["re_executions"], 1);
+    assert_eq!(report["per_transaction"]["0x0a"]["commit_latency"], 20);
+    assert_eq!(report["per_transaction"]["0x0b"]["commit_latency"], 100);
+    assert_eq!(report["critical_path"], 170);
+    assert_eq!(report["sequential_time"], 150);
+    assert_eq!(report["workers"][0]["busy"], 160);
+    assert_eq!(report["workers"][1]["idle_gaps"]["total"], 90);
+    assert!(profiler::analysis_text(&report).contains("Most re-executed transactions"));
+}
+
+#[test]
+fn test_binary_dump() {
+    let dump = serde_json::json!([
+        [
+            {
+                "type": "transaction",
+                "tx": "0x01",
+                "status": "success",
+                "runtime": 1500,
+                "start": 100,
+                "end": 1600,
+                "cpu_runtime": null,
+                "detail": { "type": "transaction", "gas": 21000, "delta": -3, "ratio": 0.25, "ok": true },
+            },
+            { "type": "other", "name": "open", "runtime": 7, "start": 5, "end": null, "detail": {} },
+        ],
+        [],
+        { "counters": [{ "type": "counter", "name": "rss_bytes", "runtime": 0, "start": 9, "end": 9, "value": 4096, "detail": {} }] },
+    ]);
+    let binary = profiler::to_binary(&dump);
+    assert!(binary.len() < serde_json::to_string(&dump).unwrap().len());
+    assert_eq!(profiler::from_binary(&binary).unwrap(), dump);
+
+    assert!(profiler::from_binary(b"not a dump").is_err());
+    assert!(profiler::from_binary(&binary[..binary.len() - 1]).is_err());
+    let mut unknown_version = binary.clone();
+    unknown_version[4] = 99;
+    assert!(profiler::from_binary(&unknown_version).is_err());
+
+    // a single event of a million nested arrays fails to decode instead of overflowing the stack
+    let depth = 1_000_000usize;
+    let mut nested = b"ABTP\x01\x00\x01\x01".to_vec();
+    let mut len = 2 * depth + 1;
+    while len >= 0x80 {
+        nested.push(len as u8 | 0x80);
+        len >>= 7;
+    }
+    nested.push(len as u8);
+    nested.extend([7, 1].repeat(depth));
+    nested.extend([0, 0]);
+    let error = profiler::from_binary(&nested).unwrap_err();
+    assert!(error.to_string().contains("nesting too deep"));
+}
+
+#[test]
+fn test_csv_export() {
+    let dump = serde_json::json!([
+        [{
+            "type": "transaction",
+            "tx": "0x01",
+            "status": "success",
+            "runtime": 10,
+            "start": 5,
+            "end": 15,
+            "detail": { "type": "transaction", "from": "a,b", "gas": { "used": 21000 } },
+        }],
+        { "counters": [{ "type": "counter", "name": "rss_bytes", "runtime": 0, "start": 9, "end": 9, "value": 4096, "detail": {} }] },
+    ]);
+
+    let csv = profiler::to_csv(&dump, None);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            "thread,type,name,start,end,runtime,status,value,detail.from,detail.gas.used,detail.type",
+            "0,transaction,0x01,5,15,10,success,,\"a,b\",21000,transaction",
+            ",counter,rss_bytes,9,9,0,,4096,,,",
+        ]
+    );
+
+    let selected = profiler::to_csv(&dump, Some(&["gas"]));
+    assert!(
+        selected.starts_with("thread,type,name,start,end,runtime,status,value,detail.gas.used\n")
+    );
+
+    // a dotted key and a nested key do not collide
+    let dotted = serde_json::json!([[{
+        "type": "other",
+        "name": "task",
+        "runtime": 1,
+        "start": 0,
+        "end": 1,
+        "detail": { "a.b": 1, "a": { "b": 2 } },
+    }]]);
+    let csv = profiler::to_csv(&dotted, None);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines[0],
+        "thread,type,name,start,end,runtime,status,detail.a.b,detail.a\\.b"
+    );
+    assert!(lines[1].ends_with(",2,1"));
+}
+
+#[test]
+fn test_dump_options() {
+    use profiler::DumpOptions;
+
+    profiler::start("options-ended");
+    profiler::note_str("options-ended", "kind", "ended");
+    profiler::end("options-ended");
+    profiler::start("options-open");
+    profiler::start_multi("options-multi");
+    profiler::end_multi("options-multi");
+
+    let events = |options: &DumpOptions| -> Vec<serde_json::Value> {
+        let output = profiler::dump_with(options);
+        let dump: serde_json::Value = serde_json::from_str(&output).unwrap();
+        dump.as_array()
+            .unwrap()
+            .iter()
+            .flat_map(|thread| thread.as_array().unwrap().clone())
+            .filter(|event| {
+                event["name"]
+                    .as_str()
+                    .is_some_and(|n| n.starts_with("options-"))
+            })
+            .collect()
+    };
+    let names = ["options-ended", "options-open", "options-multi"];
+
+    let all = events(&DumpOptions::new().names(names));
+    assert_eq!(all.len(), 3);
+
+    let compact = profiler::dump_with(&DumpOptions::new().pretty(false).names(names));
+    assert!(!compact.contains('\n'));
+
+    let closed = events(&DumpOptions::new().names(names).open_tasks(false));
+    assert_eq!(closed.len(), 2);
+    assert!(closed.iter().all(|event| !event["end"].is_null()));
+
+    let bare = events(&DumpOptions::new().names(["options-ended"]).details(false));
+    assert_eq!(bare.len(), 1);
+    assert!(bare[0].get("detail").is_none());
+
+    let ended = &all.iter().find(|e| e["name"] == "options-ended").unwrap();
+    let start = ended["start"].as_u64().unwrap();
+    let in_window = events(
+        &DumpOptions::new()
+            .names(["options-ended"])
+            .window(start, start + 1),
+    );
+    assert_eq!(in_window.len(), 1);
+    assert!(events(&DumpOptions::new().names(names).window(0, 1)).is_empty());
+
+    assert!(events(&DumpOptions::new().names(names).types(["commit"])).is_empty());
+    assert!(events(&DumpOptions::new().names(names).threads([usize::MAX])).is_empty());
+    profiler::end("options-open");
+}
+
+#[test]
+fn test_validate() {
+    let task = |name: &str, start: u64, runtime: u64, ended: bool| {
+        serde_json::json!({
+            "type": "other",
+            "name": name,
+            "runtime": runtime,
+            "start": start,
+            "end": if ended { Some(start + runtime) } else { None },
+            "detail": {},
+        })
+    };
+    let mut steady: Vec<serde_json::Value> =
+        (0..6).map(|i| task("step", i * 100, 10, true)).collect();
+    steady.push(task("step", 1000, 500, true));
+    let dump = serde_json::json!([
+        steady,
+        [
+            task("leaked", 5, 70, false),
+            task("batch-[2]", 8, 60, false),
+            task("batch-[1]", 1, 2, true)
+        ],
+    ]);
+
+    let report = profiler::validate(&dump);
+    assert_eq!(report["ok"], false);
+    assert_eq!(report["open_tasks"].as_array().unwrap().len(), 1);
+    assert_eq!(report["open_tasks"][0]["name"], "leaked");
+    assert_eq!(report["open_tasks"][0]["thread"], 1);
+    assert_eq!(report["open_multi_tasks"][0]["task"], "batch");
+    assert_eq!(report["open_multi_tasks"][0]["instance"], 2);
+    assert_eq!(report["long_spans"].as_array().unwrap().len(), 1);
+    assert_eq!(report["long_spans"][0]["runtime"], 500);
+    assert_eq!(report["long_spans"][0]["group_median"], 10);
+
+    let clean = serde_json::json!([[task("step", 0, 10, true)]]);
+    assert_eq!(profiler::validate(&clean)["ok"], true);
+}
+
+#[test]
+fn test_validate_notes() {
+    profiler::start("validate-noted");
+    profiler::note_str_unchecked("validate-noted", "key", "value");
+    profiler::note_str_unchecked("validate-note-only", "key", "value");
+    profiler::end("validate-noted");
+
+    let report: serde_json::Value = serde_json::from_str(&profiler::validation()).unwrap();
+    let issues: Vec<&serde_json::Value> = ["open_tasks", "open_multi_tasks", "long_spans"]
+        .iter()
+        .flat_map(|key| report[key].as_array().unwrap())
+        .filter(|issue| {
+            let name = issue.get("name").unwrap_or(&issue["task"]);
+            name.as_str().unwrap().starts_with("validate-note")
+        })
+        .collect();
+    assert!(issues.is_empty(), "{:?}", issues);
+}
+
+#[test]
+fn test_dump_zip_entry_name() {
+    let dir = std::env::temp_dir().join(format!("altius-dump-zip-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let output_name = dir.join("profile").to_str().unwrap().to_string();
+    profiler::dump_zip(&output_name);
+
+    // the entry is named after the whole output name, as it always was
+    let archive = std::fs::File::open(format!("{}.zip", output_name)).unwrap();
+    let archive = zip::ZipArchive::new(archive).unwrap();
+    let expected = format!("{}.json", output_name);
+    assert_eq!(
+        archive.file_names().collect::<Vec<_>>(),
+        [expected.as_str()]
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,73 @@
+use altius_benchtools::profiler;
+use serde_json::Value;
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    thread,
+};
+
+/// Accepts one OTLP/HTTP export and returns its decoded body
+fn mock_collector() -> (String, thread::JoinHandle<Value>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let endpoint = format!("http://{}/v1/traces", listener.local_addr().unwrap());
+    let handle = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line == "\r\n" {
+                break;
+            }
+            if let Some(length) = line.to_lowercase().strip_prefix("content-length:") {
+                content_length = length.trim().parse().unwrap();
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+        reader
+            .get_mut()
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n{}")
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    });
+    (endpoint, handle)
+}
+
+#[test]
+fn test_otlp_export() {
+    profiler::start("block");
+    profiler::start("execute");
+    profiler::note_str("execute", "type", "transaction");
+    profiler::note_str("execute", "status", "revert");
+    profiler::note("execute", "gas_used", serde_json::json!(21000));
+    profiler::end("execute");
+    profiler::end("block");
+
+    let (endpoint, collector) = mock_collector();
+    profiler::export_otlp(&endpoint, "otlp-test").unwrap();
+    let request = collector.join().unwrap();
+
+    let resource = &request["resourceSpans"][0];
+    assert_eq!(
+        resource["resource"]["attributes"][0]["value"]["stringValue"],
+        "otlp-test"
+    );
+    let spans = resource["scopeSpans"][0]["spans"].as_array().unwrap();
+    let block = spans.iter().find(|span| span["name"] == "block").unwrap();
+    let execute = spans
+        .iter()
+        .find(|span| span["name"] == "transaction execute")
+        .unwrap();
+    assert_eq!(execute["parentSpanId"], block["spanId"]);
+    assert_eq!(execute["traceId"], block["traceId"]);
+    assert_eq!(execute["status"]["code"], 2);
+    assert!(execute["attributes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(
+            |attribute| attribute["key"] == "gas_used" && attribute["value"]["intValue"] == "21000"
+        ));
+}
@@ -0,0 +1,42 @@
+#![cfg(feature = "parquet")]
+
+use arrow_array::{Array, StringArray, UInt64Array};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use std::fs::{self, File};
+
+#[test]
+fn test_parquet_export() {
+    let dump = serde_json::json!([
+        [
+            { "type": "transaction", "tx": "0x01", "status": "success", "runtime": 10, "start": 5, "end": 15,
+              "detail": { "gas": 21000, "note": "first" } },
+            { "type": "other", "name": "open", "runtime": 3, "start": 20, "end": null,
+              "detail": { "gas": "unknown" } },
+        ],
+    ]);
+    let path = std::env::temp_dir().join(format!("profiler-{}.parquet", std::process::id()));
+    let path = path.to_str().unwrap();
+    altius_benchtools::profiler::write_parquet(&dump, None, path).unwrap();
+
+    let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path).unwrap())
+        .unwrap()
+        .build()
+        .unwrap();
+    let batches: Vec<_> = reader.map(Result::unwrap).collect();
+    fs::remove_file(path).unwrap();
+    let batch = &batches[0];
+    assert_eq!(batch.num_rows(), 2);
+
+    let column = |name: &str| batch.column(batch.schema().index_of(name).unwrap()).clone();
+    let end = column("end");
+    let end = end.as_any().downcast_ref::<UInt64Array>().unwrap();
+    assert_eq!(end.value(0), 15);
+    assert!(end.is_null(1));
+    let name = column("name");
+    let name = name.as_any().downcast_ref::<StringArray>().unwrap();
+    assert_eq!((name.value(0), name.value(1)), ("0x01", "open"));
+    // mixed number and string values fall back to a string column
+    let gas = column("detail.gas");
+    let gas = gas.as_any().downcast_ref::<StringArray>().unwrap();
+    assert_eq!((gas.value(0), gas.value(1)), ("21000", "unknown"));
+}
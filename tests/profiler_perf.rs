@@ -0,0 +1,24 @@
+#![cfg(all(feature = "perf", target_os = "linux"))]
+
+use altius_benchtools::profiler;
+use serde_json::Value;
+
+#[test]
+fn test_perf_counters() {
+    profiler::enable_perf_counters(true);
+    profiler::start("perf-task");
+    let mut acc = 0u64;
+    for i in 0..1_000_000u64 {
+        acc = acc.wrapping_mul(31).wrapping_add(i);
+    }
+    std::hint::black_box(acc);
+    profiler::end("perf-task");
+
+    let output: Value = serde_json::from_str(&profiler::dump()).unwrap();
+    let event = &output[0][0];
+    assert_eq!(event["name"], "perf-task");
+    // perf events may be unavailable in CI containers, in which case the field is omitted
+    if let Some(perf) = event.get("perf") {
+        assert!(perf["instructions"].as_u64().unwrap() > 1_000_000);
+    }
+}
@@ -0,0 +1,29 @@
+#![cfg(target_os = "linux")]
+
+use altius_benchtools::profiler;
+use serde_json::Value;
+use std::time::Duration;
+
+#[test]
+fn test_sampler_counters() {
+    let sampler = profiler::start_sampler(Duration::from_millis(5));
+    profiler::start("sampled-task");
+    std::thread::sleep(Duration::from_millis(30));
+    profiler::end("sampled-task");
+    sampler.stop();
+
+    let output: Value = serde_json::from_str(&profiler::dump()).unwrap();
+    let counters = output.as_array().unwrap().last().unwrap()["counters"]
+        .as_array()
+        .unwrap();
+    let rss_samples = counters
+        .iter()
+        .filter(|sample| sample["name"] == "rss_bytes")
+        .count();
+    assert!(rss_samples >= 2);
+    assert!(counters.iter().all(|sample| sample["type"] == "counter"));
+    assert!(counters.iter().any(|sample| sample["name"]
+        .as_str()
+        .unwrap()
+        .starts_with("thread_cpu_ns[")));
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "http")]
+
+use altius_benchtools::profiler;
+use std::{
+    io::{Read, Write},
+    net::{SocketAddr, TcpStream},
+};
+
+fn request(addr: SocketAddr, method: &str, path: &str) -> String {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        method, path
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[test]
+fn test_http_server() {
+    let dump_dir = std::env::temp_dir().join("altius-profiler-server-test");
+    let server = profiler::start_server("127.0.0.1:0", dump_dir.to_str().unwrap()).unwrap();
+    let addr = server.addr();
+
+    profiler::start("served-task");
+    profiler::note_str("served-task", "type", "transaction");
+    profiler::note_str("served-task", "status", "success");
+    profiler::end("served-task");
+    profiler::counter("queue_depth", 7);
+
+    let metrics = request(addr, "GET", "/metrics");
+    assert!(metrics.starts_with("HTTP/1.1 200"));
+    assert!(metrics.contains(
+        "profiler_task_runtime_nanoseconds_count{type=\"transaction\",task=\"transaction\"} 1"
+    ));
+    assert!(metrics.contains("profiler_transactions{status=\"success\"} 1"));
+    assert!(metrics.contains("profiler_counter{name=\"queue_depth\"} 7"));
+
+    assert!(request(addr, "GET", "/dump").contains("\"served-task\""));
+    assert!(request(addr, "GET", "/summary").contains("\"transactions\""));
+    assert!(request(addr, "GET", "/missing").starts_with("HTTP/1.1 404"));
+
+    assert!(request(addr, "POST", "/dump_json?path=live.json").starts_with("HTTP/1.1 200"));
+    let written = std::fs::read_to_string(dump_dir.join("live.json")).unwrap();
+    assert!(written.contains("\"served-task\""));
+    assert!(request(addr, "POST", "/dump_zip?name=live").starts_with("HTTP/1.1 200"));
+    let archive = std::fs::File::open(dump_dir.join("live.zip")).unwrap();
+    let archive = zip::ZipArchive::new(archive).unwrap();
+    assert_eq!(archive.file_names().collect::<Vec<_>>(), ["live.json"]);
+    for rejected in [
+        "/tmp/escape.json",
+        "../escape.json",
+        "nested/escape.json",
+        "%2E%2E",
+    ] {
+        let response = request(addr, "POST", &format!("/dump_json?path={}", rejected));
+        assert!(response.starts_with("HTTP/1.1 400"), "{}", rejected);
+    }
+    assert!(request(addr, "POST", "/dump_zip?name=..").starts_with("HTTP/1.1 400"));
+
+    assert!(request(addr, "POST", "/clear").starts_with("HTTP/1.1 200"));
+    assert!(request(addr, "GET", "/dump").ends_with("[]"));
+
+    server.stop();
+    std::fs::remove_dir_all(dump_dir).unwrap();
+}
+
+#[test]
+fn test_http_server_rejects_non_loopback() {
+    let dump_dir = std::env::temp_dir().join("altius-profiler-server-rejected");
+    let error = profiler::start_server("0.0.0.0:0", dump_dir.to_str().unwrap()).err();
+    assert_eq!(error.unwrap().kind(), std::io::ErrorKind::InvalidInput);
+    assert!(!dump_dir.exists());
+}
@@ -0,0 +1,969 @@
+#![cfg(feature = "generator")]
+
+use altius_benchtools::{
+    constants::{
+        AMM_PAIR_DEPLOYED_BYTECODE, CREATE2_FACTORY_ADDRESS, ERC721_DEPLOYED_BYTECODE,
+        STORAGE_ACCESS_DEPLOYED_BYTECODE,
+    },
+    sign_transaction, AccessListMode, BatchConfig, ContractTemplate, DeployConfig, FeeConfig,
+    FeeDistribution, Interleaving, MixComponent, SetCodeConfig, SkewConfig, StorageConfig,
+    TransactionGenerator, TxType,
+};
+
+fn generate(seed: u64, pattern: &str, is_erc20: bool) -> String {
+    let mut generator = TransactionGenerator::with_seed(seed);
+    generator
+        .generate_pattern_transactions(pattern, 40, 4, 0.6, is_erc20)
+        .unwrap();
+    let (pre, transactions) = generator.get_data();
+    serde_json::to_string(&(pre, transactions)).unwrap()
+}
+
+type TestCase = (
+    serde_json::Map<String, serde_json::Value>,
+    Vec<serde_json::Value>,
+);
+
+/// Generates a single group of `num_transactions` transactions of a pattern with a
+/// generator seeded with `seed` and set up by `configure`
+fn generate_case(
+    seed: u64,
+    pattern: &str,
+    num_transactions: u128,
+    is_erc20: bool,
+    configure: impl FnOnce(&mut TransactionGenerator),
+) -> std::io::Result<TestCase> {
+    let mut generator = TransactionGenerator::with_seed(seed);
+    configure(&mut generator);
+    generator
+        .generate_pattern_transactions(pattern, num_transactions, 1, 0.0, is_erc20)
+        .map(|_| generator.get_data())
+}
+
+#[test]
+fn test_seeded_generation_is_reproducible() {
+    // including the patterns drawing extra random values: skewed accounts and signed
+    // authorizations
+    for pattern in [
+        "m2m",
+        "chained",
+        "o2m",
+        "m2o",
+        "s2s",
+        "zipf",
+        "hot-spot",
+        "amm",
+        "nft-mint",
+        "nft-transfer",
+        "nft-approve",
+        "erc1155-batch",
+        "deploy",
+        "create2",
+        "storage",
+        "set-code",
+    ] {
+        for is_erc20 in [false, true] {
+            assert_eq!(
+                generate(7, pattern, is_erc20),
+                generate(7, pattern, is_erc20)
+            );
+            assert_ne!(
+                generate(7, pattern, is_erc20),
+                generate(8, pattern, is_erc20)
+            );
+        }
+    }
+    // and blob contents, on fewer transactions as every blob is committed to and proven
+    #[cfg(feature = "blob")]
+    {
+        let blob = |seed| generate_case(seed, "blob", 2, false, |_| {}).unwrap();
+        assert_eq!(blob(7), blob(7));
+        assert_ne!(blob(7), blob(8));
+    }
+}
+
+/// Returns the share of account touches (as sender or recipient) that go to the `top`
+/// most touched accounts
+fn top_share(transactions: &[serde_json::Value], top: usize) -> f64 {
+    let mut touches = std::collections::HashMap::new();
+    for tx in transactions {
+        for key in ["sender", "to"] {
+            *touches
+                .entry(tx[key].as_str().unwrap().to_string())
+                .or_insert(0) += 1;
+        }
+    }
+    let mut counts: Vec<u64> = touches.into_values().collect();
+    counts.sort_unstable_by(|a, b| b.cmp(a));
+    counts.iter().take(top).sum::<u64>() as f64 / (2 * transactions.len()) as f64
+}
+
+#[test]
+fn test_skewed_patterns() {
+    let (pre, transactions) = generate_case(1, "hot-spot", 2000, false, |generator| {
+        generator.set_skew(SkewConfig {
+            accounts: Some(1000),
+            hot_fraction: 0.01,
+            hot_traffic: 0.8,
+            ..SkewConfig::default()
+        })
+    })
+    .unwrap();
+    assert_eq!(pre.len(), 1000);
+    assert_eq!(transactions.len(), 2000);
+    assert!(transactions.iter().all(|tx| tx["sender"] != tx["to"]));
+    let share = top_share(&transactions, 10);
+    assert!((0.75..0.85).contains(&share), "hot share {}", share);
+
+    let zipf_share = |exponent: f64| {
+        let (_, transactions) = generate_case(2, "zipf", 1000, false, |generator| {
+            generator.set_skew(SkewConfig {
+                accounts: Some(200),
+                zipf_exponent: exponent,
+                ..SkewConfig::default()
+            })
+        })
+        .unwrap();
+        top_share(&transactions, 1)
+    };
+    assert!(zipf_share(0.0) < 0.05);
+    assert!(zipf_share(1.5) > 0.3);
+
+    assert!(generate_case(3, "hot-spot", 100, false, |generator| {
+        generator.set_skew(SkewConfig {
+            hot_traffic: 1.5,
+            ..SkewConfig::default()
+        })
+    })
+    .is_err());
+}
+
+#[test]
+fn test_amm_pattern() {
+    let (pre, transactions) = generate_case(4, "amm", 500, false, |generator| {
+        generator.set_skew(SkewConfig {
+            accounts: Some(50),
+            zipf_exponent: 1.2,
+            pairs: 8,
+            ..SkewConfig::default()
+        })
+    })
+    .unwrap();
+    let pairs: Vec<&String> = pre
+        .iter()
+        .filter(|(_, account)| account["code"] == AMM_PAIR_DEPLOYED_BYTECODE)
+        .map(|(address, _)| address)
+        .collect();
+    assert_eq!(pairs.len(), 8);
+    // 50 traders, 8 pairs, the shared quote token and 8 base tokens
+    assert_eq!(pre.len(), 50 + 8 + 1 + 8);
+    for pair in &pairs {
+        let storage = &pre[*pair]["storage"];
+        for token in [&storage["0x00"], &storage["0x01"]] {
+            let token = token.as_str().unwrap();
+            assert!(pre[token]["storage"].as_object().unwrap().len() > 1);
+        }
+    }
+    assert_eq!(transactions.len(), 500);
+    assert!(transactions.iter().all(|tx| {
+        pairs.contains(&&tx["to"].as_str().unwrap().to_string())
+            && tx["data"].as_str().unwrap().starts_with("0x2aea6605")
+    }));
+
+    let mut swaps = std::collections::HashMap::new();
+    for tx in &transactions {
+        *swaps.entry(tx["to"].as_str().unwrap()).or_insert(0) += 1;
+    }
+    let hottest = swaps.values().max().unwrap();
+    assert!(*hottest > 500 / 8 * 2, "hottest pair has {} swaps", hottest);
+
+    assert!(generate_case(5, "amm", 100, false, |generator| {
+        generator.set_skew(SkewConfig {
+            pairs: 0,
+            ..SkewConfig::default()
+        })
+    })
+    .is_err());
+}
+
+/// Generates an NFT pattern and returns its ERC-721 contract, the number of other
+/// pre-state accounts and the transactions
+fn generate_nft(
+    seed: u64,
+    pattern: &str,
+    accounts: u128,
+) -> (serde_json::Value, usize, Vec<serde_json::Value>) {
+    let (pre, transactions) = generate_case(seed, pattern, 101, false, |generator| {
+        generator.set_skew(SkewConfig {
+            accounts: Some(accounts),
+            ..SkewConfig::default()
+        })
+    })
+    .unwrap();
+    let (contracts, others): (Vec<_>, Vec<_>) = pre
+        .values()
+        .partition(|account| account["code"] == ERC721_DEPLOYED_BYTECODE);
+    assert_eq!(contracts.len(), 1);
+    (contracts[0].clone(), others.len(), transactions)
+}
+
+#[test]
+fn test_nft_patterns() {
+    let (contract, accounts, transactions) = generate_nft(6, "nft-mint", 10);
+    assert_eq!(accounts, 10);
+    assert_eq!(contract["storage"], serde_json::json!({ "0x00": "0x00" }));
+    assert!(transactions.iter().all(|tx| tx["data"] == "0x1249c58b"));
+
+    let (contract, accounts, transactions) = generate_nft(21, "nft-transfer", 20);
+    assert_eq!(accounts, 20);
+    // token counter, then one owner and one balance per account
+    assert_eq!(contract["storage"].as_object().unwrap().len(), 1 + 20 + 20);
+    assert_eq!(contract["storage"]["0x00"], "0x14");
+    assert_eq!(transactions.len(), 101);
+    for tx in &transactions {
+        let data = tx["data"].as_str().unwrap();
+        assert!(data.starts_with("0x23b872dd"));
+        // the owner transfers its own token
+        assert_eq!(&data[34..74], &tx["sender"].as_str().unwrap()[2..]);
+    }
+
+    let (_, _, transactions) = generate_nft(22, "nft-approve", 20);
+    assert_eq!(transactions.len(), 101);
+    for pair in transactions.chunks(2) {
+        let approve = pair[0]["data"].as_str().unwrap();
+        assert!(approve.starts_with("0x095ea7b3"));
+        if let Some(transfer) = pair.get(1) {
+            let data = transfer["data"].as_str().unwrap();
+            assert!(data.starts_with("0x23b872dd"));
+            // the approved account transfers the token to itself
+            assert_eq!(&approve[34..74], &transfer["sender"].as_str().unwrap()[2..]);
+            assert_eq!(&data[98..138], &transfer["sender"].as_str().unwrap()[2..]);
+        }
+    }
+
+    assert!(generate_case(23, "nft-transfer", 10, false, |generator| {
+        generator.set_skew(SkewConfig {
+            accounts: Some(1),
+            ..SkewConfig::default()
+        })
+    })
+    .is_err());
+}
+
+/// Returns the token ids of every `safeBatchTransferFrom` call of an `erc1155-batch` test case
+fn batch_token_ids(transactions: &[serde_json::Value]) -> Vec<Vec<u128>> {
+    transactions
+        .iter()
+        .map(|tx| {
+            let data = tx["data"].as_str().unwrap();
+            assert!(data.starts_with("0x2eb2c2d6"));
+            let words: Vec<u128> = data.as_bytes()[10..]
+                .chunks(64)
+                .map(|word| {
+                    u128::from_str_radix(std::str::from_utf8(&word[32..]).unwrap(), 16).unwrap()
+                })
+                .collect();
+            // from, to, 3 offsets, then the length and items of `ids`
+            let len = words[5] as usize;
+            words[6..6 + len].to_vec()
+        })
+        .collect()
+}
+
+#[test]
+fn test_erc1155_batch_pattern() {
+    let generate = |seed: u64, batch_size: u128, id_overlap: f64| {
+        generate_case(seed, "erc1155-batch", 50, false, |generator| {
+            generator.set_skew(SkewConfig {
+                accounts: Some(10),
+                ..SkewConfig::default()
+            });
+            generator.set_batch(BatchConfig {
+                batch_size,
+                id_overlap,
+            });
+        })
+    };
+
+    let (pre, transactions) = generate(24, 8, 0.25).unwrap();
+    assert_eq!(pre.len(), 10 + 1);
+    let batches = batch_token_ids(&transactions);
+    assert!(batches.iter().all(|ids| ids.len() == 8));
+    // 2 shared ids (out of 8) and 6 unique ids per batch
+    for ids in &batches {
+        assert_eq!(ids.iter().filter(|&&id| id < 8).count(), 2);
+    }
+    let unique: std::collections::HashSet<u128> = batches.iter().flatten().copied().collect();
+    assert_eq!(unique.iter().filter(|&&id| id >= 8).count(), 50 * 6);
+    // every sender is funded for the ids it sends
+    let contract = pre
+        .values()
+        .find(|account| account["code"].as_str().unwrap().len() > 2)
+        .unwrap();
+    assert!(contract["storage"].as_object().unwrap().len() >= 50 * 6);
+
+    let (_, transactions) = generate(25, 4, 1.0).unwrap();
+    assert!(batch_token_ids(&transactions)
+        .iter()
+        .flatten()
+        .all(|&id| id < 4));
+    let (_, transactions) = generate(26, 4, 0.0).unwrap();
+    let ids: Vec<u128> = batch_token_ids(&transactions)
+        .into_iter()
+        .flatten()
+        .collect();
+    assert_eq!(
+        ids.iter().collect::<std::collections::HashSet<_>>().len(),
+        ids.len()
+    );
+
+    assert!(generate(27, 0, 0.5).is_err());
+    assert!(generate(28, 4, 1.5).is_err());
+}
+
+#[test]
+fn test_deploy_patterns() {
+    let generate =
+        |seed: u64, pattern: &str, template: ContractTemplate, code_size: Option<usize>| {
+            generate_case(seed, pattern, 10, false, |generator| {
+                generator.set_deploy(DeployConfig {
+                    template,
+                    code_size,
+                })
+            })
+        };
+
+    let (pre, transactions) = generate(9, "deploy", ContractTemplate::Erc721, None).unwrap();
+    assert_eq!(pre.len(), 10);
+    assert!(transactions.iter().all(|tx| tx["to"] == ""));
+    // the init code returns the runtime code appended to it
+    let data = transactions[0]["data"].as_str().unwrap();
+    assert!(data.ends_with(&ERC721_DEPLOYED_BYTECODE[2..]));
+
+    // 13 bytes of init code returning 1000 bytes of code
+    let (_, transactions) = generate(29, "deploy", ContractTemplate::Empty, Some(1000)).unwrap();
+    for tx in &transactions {
+        assert_eq!(tx["data"].as_str().unwrap().len(), 2 + 2 * (13 + 1000));
+    }
+    assert_ne!(transactions[0]["data"], transactions[1]["data"]);
+
+    let (pre, transactions) = generate(30, "create2", ContractTemplate::Empty, Some(1000)).unwrap();
+    assert!(pre.contains_key(CREATE2_FACTORY_ADDRESS));
+    for tx in &transactions {
+        assert_eq!(tx["to"], CREATE2_FACTORY_ADDRESS);
+        // salt, then the init code
+        assert_eq!(tx["data"].as_str().unwrap().len(), 2 + 64 + 2 * (13 + 1000));
+    }
+
+    assert!(generate(31, "deploy", ContractTemplate::Empty, Some(24_577)).is_err());
+    assert_eq!(
+        "erc1155".parse::<ContractTemplate>().unwrap(),
+        ContractTemplate::Erc1155
+    );
+    assert!("erc4626".parse::<ContractTemplate>().is_err());
+}
+
+#[test]
+fn test_storage_pattern() {
+    let (pre, transactions) = generate_case(10, "storage", 30, false, |generator| {
+        generator.set_storage(StorageConfig {
+            slots_per_tx: 20,
+            write_ratio: 0.25,
+            key_overlap: 0.5,
+        })
+    })
+    .unwrap();
+
+    let mut accesses = std::collections::HashMap::new();
+    for tx in &transactions {
+        let data = tx["data"].as_str().unwrap();
+        let words: Vec<&str> = (2..data.len())
+            .step_by(64)
+            .map(|i| &data[i..i + 64])
+            .collect();
+        assert_eq!(words.len(), 20);
+        // the top bit of a word marks a write
+        let writes = words
+            .iter()
+            .filter(|word| word.as_bytes()[0] >= b'8')
+            .count();
+        assert_eq!(writes, 5);
+        for word in words {
+            let slot =
+                format!("{:x}", u8::from_str_radix(&word[..1], 16).unwrap() & 7) + &word[1..];
+            *accesses.entry(slot).or_insert(0) += 1;
+        }
+    }
+    // 10 slots are accessed by every transaction, the 10 others of each by it alone
+    assert_eq!(accesses.values().filter(|&&count| count == 30).count(), 10);
+    assert_eq!(accesses.len(), 10 + 30 * 10);
+
+    // every accessed slot holds a value in `pre`
+    let storage = pre
+        .values()
+        .find_map(|account| account["storage"].as_object().filter(|s| !s.is_empty()))
+        .unwrap();
+    assert_eq!(storage.len(), accesses.len());
+    assert!(accesses
+        .keys()
+        .all(|slot| storage.contains_key(&format!("0x{}", slot))));
+
+    assert!(generate_case(11, "storage", 10, false, |generator| {
+        generator.set_storage(StorageConfig {
+            write_ratio: 2.0,
+            ..StorageConfig::default()
+        })
+    })
+    .is_err());
+}
+
+fn generate_mix(mix: &str, interleaving: Interleaving) -> Vec<serde_json::Value> {
+    let components: Vec<MixComponent> = mix.split(',').map(|c| c.parse().unwrap()).collect();
+    let mut generator = TransactionGenerator::with_seed(12);
+    generator
+        .generate_mixed_transactions(&components, 40, 2, 0.5, interleaving)
+        .unwrap();
+    generator.get_data().1
+}
+
+fn selector(tx: &serde_json::Value) -> &str {
+    let data = tx["data"].as_str().unwrap();
+    &data[..data.len().min(10)]
+}
+
+#[test]
+fn test_mixed_workload() {
+    // ETH transfers, ERC20 transfers and swaps, split 20 / 10 / 10
+    let mix = "m2m=2,o2m:erc20=1,amm=1";
+    let sequential = generate_mix(mix, Interleaving::Sequential);
+    let kinds: Vec<&str> = sequential.iter().map(selector).collect();
+    assert!(kinds[..20].iter().all(|&kind| kind == "0x"));
+    assert!(kinds[20..30].iter().all(|&kind| kind == "0xa9059cbb"));
+    assert!(kinds[30..].iter().all(|&kind| kind == "0x2aea6605"));
+
+    let round_robin = generate_mix(mix, Interleaving::RoundRobin);
+    let kinds: Vec<&str> = round_robin.iter().map(selector).collect();
+    assert_eq!(kinds[..6], ["0x", "0xa9059cbb", "0x2aea6605"].repeat(2));
+    assert!(kinds[30..].iter().all(|&kind| kind == "0x"));
+
+    let shuffled = generate_mix(mix, Interleaving::Shuffle);
+    assert_ne!(shuffled, sequential);
+    let sorted = |transactions: &[serde_json::Value]| {
+        let mut txs: Vec<String> = transactions.iter().map(|tx| tx.to_string()).collect();
+        txs.sort();
+        txs
+    };
+    assert_eq!(sorted(&shuffled), sorted(&sequential));
+    let mut nonces = std::collections::HashMap::new();
+    for tx in &shuffled {
+        let nonce = u64::from_str_radix(&tx["nonce"].as_str().unwrap()[2..], 16).unwrap();
+        let expected = nonces.entry(tx["sender"].to_string()).or_insert(0);
+        assert_eq!(nonce, *expected);
+        *expected += 1;
+    }
+
+    // patterns sharing the ERC20 contract keep each other's balances in `pre`: the 2 of
+    // the o2m senders and the 20 of the m2o senders
+    let components = ["o2m:erc20".parse().unwrap(), "m2o:erc20".parse().unwrap()];
+    let mut generator = TransactionGenerator::with_seed(13);
+    generator
+        .generate_mixed_transactions(&components, 40, 2, 0.0, Interleaving::Shuffle)
+        .unwrap();
+    let (pre, transactions) = generator.get_data();
+    let erc20 = &transactions[0]["to"];
+    assert!(transactions.iter().all(|tx| &tx["to"] == erc20));
+    let balances = pre[erc20.as_str().unwrap()]["storage"].as_object().unwrap();
+    assert_eq!(balances.len(), 2 + 20);
+
+    assert!("m2m:erc721".parse::<MixComponent>().is_err());
+    assert!("round-robin".parse::<Interleaving>().is_ok());
+    let mut invalid = TransactionGenerator::with_seed(14);
+    assert!(invalid
+        .generate_mixed_transactions(&[], 10, 1, 0.0, Interleaving::Sequential)
+        .is_err());
+}
+
+fn hex_quantity(value: &serde_json::Value) -> u128 {
+    u128::from_str_radix(&value.as_str().unwrap()[2..], 16).unwrap()
+}
+
+#[test]
+fn test_transaction_types() {
+    // the same test case under different fee configurations
+    let generate_typed = |fees: FeeConfig| {
+        generate_case(15, "o2m", 20, false, |generator| generator.set_fees(fees)).unwrap()
+    };
+    let (legacy_pre, legacy) = generate_typed(FeeConfig::default());
+    assert!(legacy
+        .iter()
+        .all(|tx| tx["gasPrice"] == "0x0a" && tx.get("accessList").is_none()));
+
+    let (_, access_list) = generate_typed(FeeConfig {
+        tx_type: TxType::AccessList,
+        ..FeeConfig::default()
+    });
+    assert!(access_list
+        .iter()
+        .all(|tx| tx["gasPrice"] == "0x0a" && tx["accessList"] == serde_json::json!([])));
+
+    let (pre, dynamic_fee) = generate_typed(FeeConfig {
+        tx_type: TxType::DynamicFee,
+        gas_price: "20..1000000000000".parse().unwrap(),
+        priority_fee: FeeDistribution::Uniform {
+            min: 1,
+            max: 2_000_000_000_000,
+        },
+    });
+    let mut fees = 0;
+    for tx in &dynamic_fee {
+        assert!(tx.get("gasPrice").is_none());
+        let max_fee = hex_quantity(&tx["maxFeePerGas"]);
+        assert!((20..=1_000_000_000_000).contains(&max_fee));
+        assert!(hex_quantity(&tx["maxPriorityFeePerGas"]) <= max_fee);
+        fees += hex_quantity(&tx["gasLimit"]) * max_fee;
+    }
+    // the single sender is funded with the fees on top of its usual balance
+    let legacy_fees: u128 = legacy
+        .iter()
+        .map(|tx| hex_quantity(&tx["gasLimit"]) * 10)
+        .sum();
+    let sender = dynamic_fee[0]["sender"].as_str().unwrap();
+    assert_eq!(
+        hex_quantity(&pre[sender]["balance"]) + legacy_fees,
+        hex_quantity(&legacy_pre[sender]["balance"]) + fees
+    );
+
+    assert_eq!("eip1559".parse::<TxType>().unwrap(), TxType::DynamicFee);
+    assert_eq!(
+        "7".parse::<FeeDistribution>().unwrap(),
+        FeeDistribution::Fixed(7)
+    );
+    assert!("100..10".parse::<FeeDistribution>().is_err());
+    // fees overflowing the balance of a sender are rejected rather than under-funded
+    assert!(generate_case(43, "o2m", 20, false, |generator| {
+        generator.set_fees(FeeConfig {
+            gas_price: FeeDistribution::Fixed(u128::MAX / 1_000_000),
+            ..FeeConfig::default()
+        })
+    })
+    .is_err());
+    assert!("3".parse::<TxType>().is_err());
+}
+
+#[test]
+fn test_access_lists() {
+    // the same test case with different access lists
+    let generate_with = |access_lists: AccessListMode| {
+        generate_case(16, "storage", 20, false, |generator| {
+            generator.set_fees(FeeConfig {
+                tx_type: TxType::AccessList,
+                ..FeeConfig::default()
+            });
+            generator.set_access_lists(access_lists);
+        })
+        .unwrap()
+    };
+    let (_, empty) = generate_with(AccessListMode::Empty);
+    let (pre, precise) = generate_with(AccessListMode::Precise);
+    let contract = precise[0]["to"].as_str().unwrap();
+    let storage = pre[contract]["storage"].as_object().unwrap();
+    for (tx, empty_tx) in precise.iter().zip(&empty) {
+        assert_eq!(empty_tx["accessList"], serde_json::json!([]));
+        // every slot of the calldata, which all hold a value in `pre`
+        let access_list = tx["accessList"].as_array().unwrap();
+        assert_eq!(access_list.len(), 1);
+        assert_eq!(access_list[0]["address"], contract);
+        let keys = access_list[0]["storageKeys"].as_array().unwrap();
+        assert_eq!(keys.len(), 10);
+        assert!(keys
+            .iter()
+            .all(|key| storage.contains_key(key.as_str().unwrap())));
+        // the gas limit covers the intrinsic gas of the access list
+        assert_eq!(
+            hex_quantity(&tx["gasLimit"]),
+            hex_quantity(&empty_tx["gasLimit"]) + 2400 + 1900 * 10
+        );
+    }
+
+    let (_, partial) = generate_with(AccessListMode::Partial { coverage: 0.5 });
+    let partial_keys: usize = partial
+        .iter()
+        .flat_map(|tx| tx["accessList"].as_array().unwrap())
+        .map(|entry| entry["storageKeys"].as_array().unwrap().len())
+        .sum();
+    assert!((50..150).contains(&partial_keys));
+
+    let (_, wrong) = generate_with(AccessListMode::Wrong);
+    for (tx, precise_tx) in wrong.iter().zip(&precise) {
+        let keys = &tx["accessList"][0]["storageKeys"];
+        let precise_keys = precise_tx["accessList"][0]["storageKeys"]
+            .as_array()
+            .unwrap();
+        assert_eq!(keys.as_array().unwrap().len(), 10);
+        assert!(keys
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|key| !precise_keys.contains(key)));
+    }
+
+    // ERC20 transfers declare the balances of the sender and the receiver
+    let (pre, transactions) = generate_case(17, "m2m", 10, true, |generator| {
+        generator.set_fees(FeeConfig {
+            tx_type: TxType::DynamicFee,
+            ..FeeConfig::default()
+        });
+        generator.set_access_lists("precise".parse().unwrap());
+    })
+    .unwrap();
+    for tx in &transactions {
+        let access_list = tx["accessList"].as_array().unwrap();
+        assert_eq!(access_list.len(), 1);
+        assert_eq!(access_list[0]["address"], tx["to"]);
+        let keys = access_list[0]["storageKeys"].as_array().unwrap();
+        assert_eq!(keys.len(), 2);
+        let balances = pre[tx["to"].as_str().unwrap()]["storage"]
+            .as_object()
+            .unwrap();
+        assert!(balances.contains_key(keys[0].as_str().unwrap()));
+    }
+
+    // legacy transactions have no access list
+    assert!(generate_case(32, "m2m", 10, false, |generator| {
+        generator.set_access_lists(AccessListMode::Precise)
+    })
+    .is_err());
+    // but set-code transactions are typed whatever the configured transaction type
+    let (_, transactions) = generate_case(42, "set-code", 4, false, |generator| {
+        generator.set_access_lists(AccessListMode::Precise)
+    })
+    .unwrap();
+    for tx in &transactions {
+        match tx.get("authorizationList") {
+            Some(_) => {
+                // the counter in the storage of the delegated account
+                assert_eq!(tx["accessList"][0]["address"], tx["to"]);
+                assert_eq!(
+                    tx["accessList"][0]["storageKeys"].as_array().unwrap().len(),
+                    1
+                );
+            }
+            None => assert!(tx.get("accessList").is_none()),
+        }
+    }
+    assert_eq!(
+        "partial=0.2".parse::<AccessListMode>().unwrap(),
+        AccessListMode::Partial { coverage: 0.2 }
+    );
+    assert!(generate_case(33, "m2m", 10, false, |generator| {
+        generator.set_fees(FeeConfig {
+            tx_type: TxType::AccessList,
+            ..FeeConfig::default()
+        });
+        generator.set_access_lists("partial=2".parse().unwrap());
+    })
+    .is_err());
+    assert!("exact".parse::<AccessListMode>().is_err());
+}
+
+#[test]
+fn test_set_code_pattern() {
+    use ethers::{
+        types::{Address, Signature, H256, U256},
+        utils::{keccak256, rlp::RlpStream},
+    };
+    use std::str::FromStr;
+
+    let (pre, transactions) = generate_case(36, "set-code", 20, false, |generator| {
+        generator.set_skew(SkewConfig {
+            accounts: Some(3),
+            ..SkewConfig::default()
+        });
+        generator.set_set_code(SetCodeConfig {
+            authorizations_per_tx: 4,
+            call_ratio: 0.25,
+        });
+    })
+    .unwrap();
+    let (set_code, calls) = transactions.split_at(15);
+
+    // the authorizations of every account carry its successive nonces
+    let mut nonces = std::collections::HashMap::new();
+    for tx in set_code {
+        let authorizations = tx["authorizationList"].as_array().unwrap();
+        assert_eq!(authorizations.len(), 4);
+        assert_eq!(tx["to"], authorizations[0]["signer"]);
+        for authorization in authorizations {
+            let delegate = authorization["address"].as_str().unwrap();
+            assert_eq!(pre[delegate]["code"], STORAGE_ACCESS_DEPLOYED_BYTECODE);
+            let nonce = nonces
+                .entry(authorization["signer"].as_str().unwrap().to_string())
+                .or_insert(0);
+            assert_eq!(hex_quantity(&authorization["nonce"]), *nonce);
+            *nonce += 1;
+
+            let mut rlp = RlpStream::new_list(3);
+            rlp.append(&1u64);
+            rlp.append(&Address::from_str(delegate).unwrap());
+            rlp.append(&(hex_quantity(&authorization["nonce"]) as u64));
+            let hash = keccak256([&[0x05], rlp.as_raw()].concat());
+            let signature = Signature {
+                r: U256::from_str(authorization["r"].as_str().unwrap()).unwrap(),
+                s: U256::from_str(authorization["s"].as_str().unwrap()).unwrap(),
+                v: hex_quantity(&authorization["yParity"]) as u64 + 27,
+            };
+            assert_eq!(
+                signature.recover(H256::from(hash)).unwrap(),
+                Address::from_str(authorization["signer"].as_str().unwrap()).unwrap()
+            );
+        }
+    }
+    // 60 authorizations of 3 accounts
+    assert_eq!(nonces.len(), 3);
+    assert_eq!(nonces.values().sum::<u128>(), 60);
+    for tx in calls {
+        assert!(tx.get("authorizationList").is_none());
+        assert!(nonces.contains_key(tx["to"].as_str().unwrap()));
+    }
+
+    // without an account pool, every authorization delegates a new account
+    let (_, transactions) = generate_case(37, "set-code", 10, false, |_| {}).unwrap();
+    let signers: std::collections::HashSet<&str> = transactions[..5]
+        .iter()
+        .map(|tx| tx["authorizationList"][0]["signer"].as_str().unwrap())
+        .collect();
+    assert_eq!(signers.len(), 5);
+}
+
+#[test]
+fn test_raw_transactions() {
+    use ethers::{
+        types::{transaction::eip2718::TypedTransaction, Address, U64},
+        utils::{hex, keccak256, rlp::Rlp},
+    };
+    use std::str::FromStr;
+
+    for (seed, tx_type, prefix) in [
+        (20, TxType::Legacy, None),
+        (38, TxType::AccessList, Some(1)),
+        (39, TxType::DynamicFee, Some(2)),
+    ] {
+        let (_, transactions) = generate_case(seed, "m2m", 10, true, |generator| {
+            generator.set_fees(FeeConfig {
+                tx_type,
+                gas_price: FeeDistribution::Uniform { min: 10, max: 100 },
+                priority_fee: FeeDistribution::Fixed(2),
+            })
+        })
+        .unwrap();
+        for tx in &transactions {
+            let signed = sign_transaction(tx, 1337).unwrap();
+            let raw = hex::decode(signed["raw"].as_str().unwrap()).unwrap();
+            assert_eq!(
+                signed["hash"].as_str().unwrap(),
+                format!("0x{}", hex::encode(keccak256(&raw)))
+            );
+            match prefix {
+                Some(prefix) => assert_eq!(raw[0], prefix),
+                // legacy transactions are a bare RLP list
+                None => assert!(raw[0] >= 0xc0),
+            }
+
+            let (decoded, signature) = TypedTransaction::decode_signed(&Rlp::new(&raw)).unwrap();
+            assert_eq!(decoded.chain_id(), Some(U64::from(1337)));
+            assert_eq!(
+                decoded.nonce().unwrap().as_u128(),
+                hex_quantity(&tx["nonce"])
+            );
+            assert_eq!(
+                signature.recover(decoded.sighash()).unwrap(),
+                Address::from_str(tx["sender"].as_str().unwrap()).unwrap()
+            );
+        }
+    }
+
+    // the authorizations of set-code transactions are signed for the chain id of the generator
+    let (_, transactions) = generate_case(40, "set-code", 4, false, |generator| {
+        generator.set_chain_id(1337)
+    })
+    .unwrap();
+    assert_eq!(transactions[0]["authorizationList"][0]["chainId"], "0x0539");
+    let raw = sign_transaction(&transactions[0], 1337).unwrap()["raw"].clone();
+    assert!(raw.as_str().unwrap().starts_with("0x04"));
+
+    let mut missing_key = transactions[0].clone();
+    missing_key.as_object_mut().unwrap().remove("secretKey");
+    assert!(sign_transaction(&missing_key, 1337).is_err());
+}
+
+#[cfg(feature = "blob")]
+#[test]
+fn test_blob_pattern() {
+    use altius_benchtools::BlobConfig;
+    use sha2::{Digest, Sha256};
+
+    let (pre, transactions) = generate_case(34, "blob", 4, false, |generator| {
+        generator.set_blobs(BlobConfig {
+            blobs_per_tx: 1..=2,
+            max_fee_per_blob_gas: FeeDistribution::Uniform { min: 1, max: 100 },
+            sidecars: true,
+        })
+    })
+    .unwrap();
+    let settings = c_kzg::ethereum_kzg_settings(0);
+    for tx in &transactions {
+        let hashes = tx["blobVersionedHashes"].as_array().unwrap();
+        assert!((1..=2).contains(&hashes.len()));
+        assert!((1..=100).contains(&hex_quantity(&tx["maxFeePerBlobGas"])));
+        assert!(tx.get("gasPrice").is_none());
+        // the sender can afford the blob gas on top of the execution gas
+        let balance = hex_quantity(&pre[tx["sender"].as_str().unwrap()]["balance"]);
+        assert!(balance >= hashes.len() as u128 * 131_072 * hex_quantity(&tx["maxFeePerBlobGas"]));
+
+        let decode = |value: &serde_json::Value| {
+            ethers::utils::hex::decode(value.as_str().unwrap()).unwrap()
+        };
+        for (i, hash) in hashes.iter().enumerate() {
+            let blob = c_kzg::Blob::from_bytes(&decode(&tx["blobs"][i])).unwrap();
+            let commitment = c_kzg::Bytes48::from_bytes(&decode(&tx["commitments"][i])).unwrap();
+            let proof = c_kzg::Bytes48::from_bytes(&decode(&tx["proofs"][i])).unwrap();
+            assert!(settings
+                .verify_blob_kzg_proof(&blob, &commitment, &proof)
+                .unwrap());
+            let mut versioned_hash: [u8; 32] = Sha256::digest(commitment.as_slice()).into();
+            versioned_hash[0] = 0x01;
+            assert_eq!(decode(hash), versioned_hash);
+        }
+    }
+
+    // without sidecars only the versioned hashes are kept
+    let (_, transactions) = generate_case(35, "blob", 2, false, |_| {}).unwrap();
+    for tx in &transactions {
+        assert_eq!(tx["blobVersionedHashes"].as_array().unwrap().len(), 1);
+        assert!(tx.get("blobs").is_none() && tx.get("commitments").is_none());
+    }
+}
+
+#[cfg(feature = "blob")]
+#[test]
+fn test_sign_blob_transactions() {
+    use altius_benchtools::BlobConfig;
+    use ethers::utils::{hex, keccak256, rlp::Rlp};
+
+    let (_, transactions) = generate_case(41, "blob", 2, false, |generator| {
+        generator.set_blobs(BlobConfig {
+            sidecars: true,
+            ..BlobConfig::default()
+        })
+    })
+    .unwrap();
+    let decode = |value: &serde_json::Value| hex::decode(value.as_str().unwrap()).unwrap();
+    for tx in &transactions {
+        let signed = sign_transaction(tx, 1).unwrap();
+        let raw = decode(&signed["raw"]);
+        assert_eq!(raw[0], 3);
+
+        // the network form is the signed transaction followed by its blobs, commitments and proofs
+        let network = Rlp::new(&raw[1..]);
+        assert_eq!(network.item_count().unwrap(), 4);
+        for (idx, field) in ["blobs", "commitments", "proofs"].into_iter().enumerate() {
+            let items: Vec<Vec<u8>> = network.at(idx + 1).unwrap().as_list().unwrap();
+            let expected: Vec<_> = tx[field].as_array().unwrap().iter().map(decode).collect();
+            assert_eq!(items, expected);
+        }
+        let body = network.at(0).unwrap();
+        assert_eq!(body.item_count().unwrap(), 14);
+        let envelope = [&[3], body.as_raw()].concat();
+        assert_eq!(decode(&signed["hash"]), keccak256(&envelope));
+
+        // the hash and the signature are those of the transaction without sidecars
+        let mut bare = tx.clone();
+        for field in ["blobs", "commitments", "proofs"] {
+            bare.as_object_mut().unwrap().remove(field);
+        }
+        let bare = sign_transaction(&bare, 1).unwrap();
+        assert_eq!(bare["hash"], signed["hash"]);
+        assert_eq!(decode(&bare["raw"]), envelope);
+    }
+}
+
+#[test]
+fn test_scenario_file() {
+    let dir = std::env::temp_dir().join(format!("generate-scenario-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let scenario = dir.join("scenario.toml");
+    std::fs::write(
+        &scenario,
+        format!(
+            r#"
+[defaults]
+num-groups = 2
+output = "{}/{{name}}.json"
+env = {{ currentGasLimit = "0x0bebc200" }}
+
+[[cases]]
+name = "m2m"
+type = "m2m"
+num-transactions = [10, 20]
+seed = 1
+
+[[cases]]
+name = "erc20"
+type = "o2m"
+num-transactions = 10
+erc20 = true
+fork = "Prague"
+"#,
+            dir.display()
+        ),
+    )
+    .unwrap();
+    let status = std::process::Command::new(env!("CARGO_BIN_EXE_generate"))
+        .arg("scenario")
+        .arg(&scenario)
+        .output()
+        .unwrap()
+        .status;
+    assert!(status.success());
+
+    let read = |name: &str| -> serde_json::Value {
+        let file = std::fs::read_to_string(dir.join(format!("{}.json", name))).unwrap();
+        serde_json::from_str::<serde_json::Value>(&file).unwrap()[name].clone()
+    };
+    for (name, transactions) in [
+        ("m2m-num-transactions-10", 10),
+        ("m2m-num-transactions-20", 20),
+    ] {
+        let case = read(name);
+        assert_eq!(case["transaction"].as_array().unwrap().len(), transactions);
+        assert_eq!(case["env"]["currentGasLimit"], "0x0bebc200");
+        assert!(case["post"]["Cancun"].is_object());
+    }
+    let case = read("erc20");
+    assert!(case["post"]["Prague"].is_object());
+    assert!(case["transaction"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|tx| tx["data"].as_str().unwrap().starts_with("0xa9059cbb")));
+
+    // YAML files are read too, and a case without a name is rejected
+    let run_yaml = |yaml: &str| {
+        let scenario = dir.join("scenario.yaml");
+        std::fs::write(&scenario, yaml).unwrap();
+        std::process::Command::new(env!("CARGO_BIN_EXE_generate"))
+            .arg("scenario")
+            .arg(&scenario)
+            .output()
+            .unwrap()
+            .status
+    };
+    let yaml = format!(
+        "cases:\n  - name: yaml\n    type: chained\n    output: {}/{{name}}.json\n",
+        dir.display()
+    );
+    assert!(run_yaml(&yaml).success());
+    assert_eq!(read("yaml")["transaction"].as_array().unwrap().len(), 20);
+    assert!(!run_yaml("cases:\n  - type: m2m\n").success());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
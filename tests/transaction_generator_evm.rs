@@ -0,0 +1,455 @@
+#![cfg(feature = "generator")]
+
+use altius_benchtools::{
+    constants::{ERC20_USDC_DEPLOYED_BYTECODE, ERC721_DEPLOYED_BYTECODE},
+    AccessListMode, BatchConfig, ContractTemplate, DeployConfig, FeeConfig, SetCodeConfig,
+    SkewConfig, StorageConfig, TransactionGenerator, TxType,
+};
+use revm::{
+    context::{
+        transaction::{AccessList, AccessListItem, Authorization, SignedAuthorization},
+        TxEnv,
+    },
+    database::{CacheDB, EmptyDB},
+    handler::{MainnetContext, MainnetEvm},
+    primitives::{hardfork::SpecId, Address, Bytes, TxKind, B256, U256},
+    state::{AccountInfo, Bytecode, EvmState},
+    Context, ExecuteCommitEvm, ExecuteEvm, MainBuilder, MainContext,
+};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+fn quantity(value: &Value) -> U256 {
+    match value.as_str().unwrap().trim_start_matches("0x") {
+        "" => U256::ZERO,
+        digits => U256::from_str_radix(digits, 16).unwrap(),
+    }
+}
+
+fn address(value: &Value) -> Address {
+    value.as_str().unwrap().parse().unwrap()
+}
+
+fn bytes(value: &Value) -> Bytes {
+    value.as_str().unwrap().parse().unwrap()
+}
+
+fn storage_keys(entry: &Value) -> Vec<B256> {
+    let keys = entry["storageKeys"].as_array().unwrap();
+    keys.iter()
+        .map(|key| key.as_str().unwrap().parse().unwrap())
+        .collect()
+}
+
+/// Storage slots of every account, as in the access lists of the generator
+type Slots = BTreeMap<Address, BTreeSet<B256>>;
+
+/// Runs the transactions of a generated test case in an EVM loaded with its pre-state
+struct Chain {
+    evm: MainnetEvm<MainnetContext<CacheDB<EmptyDB>>>,
+}
+
+impl Chain {
+    fn new(pre: &Map<String, Value>) -> Self {
+        let mut db = CacheDB::new(EmptyDB::default());
+        for (account, state) in pre {
+            let account = account.parse().unwrap();
+            let code = Bytecode::new_raw(bytes(&state["code"]));
+            let balance = quantity(&state["balance"]);
+            let nonce = quantity(&state["nonce"]).to();
+            db.insert_account_info(
+                account,
+                AccountInfo::new(balance, nonce, code.hash_slow(), code),
+            );
+            for (slot, value) in state["storage"].as_object().unwrap() {
+                let slot = quantity(&Value::from(slot.as_str()));
+                db.insert_account_storage(account, slot, quantity(value));
+            }
+        }
+        let evm = Context::mainnet()
+            .with_db(db)
+            .modify_cfg_chained(|cfg| cfg.spec = SpecId::PRAGUE)
+            .modify_block_chained(|block| block.basefee = 1)
+            .build_mainnet();
+        Self { evm }
+    }
+
+    /// Executes a transaction, with or without its access list, asserting that it
+    /// succeeds, and returns the state it touched, committing it unless `dry_run` is set
+    fn execute(&mut self, tx: &Value, access_list: bool, dry_run: bool) -> EvmState {
+        let fee = |key: &str| tx.get(key).map(|fee| quantity(fee).to::<u128>());
+        let (tx_type, gas_price, gas_priority_fee) = match fee("maxFeePerGas") {
+            Some(max_fee) if tx.get("blobVersionedHashes").is_some() => {
+                (3, max_fee, fee("maxPriorityFeePerGas"))
+            }
+            Some(max_fee) if tx.get("authorizationList").is_some() => {
+                (4, max_fee, fee("maxPriorityFeePerGas"))
+            }
+            Some(max_fee) => (2, max_fee, fee("maxPriorityFeePerGas")),
+            None if tx.get("accessList").is_some() => (1, fee("gasPrice").unwrap(), None),
+            None => (0, fee("gasPrice").unwrap(), None),
+        };
+        let access_list = match access_list {
+            true => AccessList(
+                tx["accessList"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|entry| AccessListItem {
+                        address: address(&entry["address"]),
+                        storage_keys: storage_keys(entry),
+                    })
+                    .collect(),
+            ),
+            false => AccessList::default(),
+        };
+        let mut tx_env = TxEnv {
+            tx_type,
+            caller: address(&tx["sender"]),
+            gas_limit: quantity(&tx["gasLimit"]).to(),
+            gas_price,
+            gas_priority_fee,
+            kind: match tx["to"].as_str().unwrap() {
+                "" => TxKind::Create,
+                to => TxKind::Call(to.parse().unwrap()),
+            },
+            value: quantity(&tx["value"]),
+            data: bytes(&tx["data"]),
+            nonce: quantity(&tx["nonce"]).to(),
+            chain_id: Some(1),
+            access_list,
+            blob_hashes: tx["blobVersionedHashes"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|hash| hash.as_str().unwrap().parse().unwrap())
+                .collect(),
+            max_fee_per_blob_gas: fee("maxFeePerBlobGas").unwrap_or(0),
+            ..TxEnv::default()
+        };
+        let authorizations = tx["authorizationList"].as_array().into_iter().flatten();
+        tx_env.set_signed_authorization(
+            authorizations
+                .map(|authorization| {
+                    let inner = Authorization {
+                        chain_id: quantity(&authorization["chainId"]),
+                        address: address(&authorization["address"]),
+                        nonce: quantity(&authorization["nonce"]).to(),
+                    };
+                    let y_parity = quantity(&authorization["yParity"]).to();
+                    let (r, s) = (quantity(&authorization["r"]), quantity(&authorization["s"]));
+                    SignedAuthorization::new_unchecked(inner, y_parity, r, s)
+                })
+                .collect(),
+        );
+        let outcome = self.evm.transact(tx_env).unwrap();
+        assert!(outcome.result.is_success(), "{:?}", outcome.result);
+        if !dry_run {
+            self.evm.commit(outcome.state.clone());
+        }
+        outcome.state
+    }
+}
+
+/// Returns the slots of the accounts with storage in a state or an access list
+fn slots(accounts: impl Iterator<Item = (Address, Vec<B256>)>) -> Slots {
+    accounts
+        .filter(|(_, keys)| !keys.is_empty())
+        .map(|(account, keys)| (account, keys.into_iter().collect()))
+        .collect()
+}
+
+/// Generates a test case of a pattern with precise access lists
+fn generate_precise(
+    seed: u64,
+    pattern: &str,
+    num_transactions: u128,
+    configure: impl FnOnce(&mut TransactionGenerator),
+) -> (Map<String, Value>, Vec<Value>) {
+    let mut generator = TransactionGenerator::with_seed(seed);
+    generator.set_fees(FeeConfig {
+        tx_type: TxType::AccessList,
+        ..FeeConfig::default()
+    });
+    generator.set_access_lists(AccessListMode::Precise);
+    configure(&mut generator);
+    generator
+        .generate_pattern_transactions(pattern, num_transactions, 1, 0.0, false)
+        .unwrap();
+    generator.get_data()
+}
+
+/// Executes the transactions of a test case in order, asserting that the access list of
+/// each declares exactly the storage slots it accesses, and returns the state each touched
+fn execute_precise(pre: &Map<String, Value>, transactions: &[Value]) -> Vec<EvmState> {
+    let mut chain = Chain::new(pre);
+    transactions
+        .iter()
+        .map(|tx| {
+            // without an access list, the state holds only the slots the transaction loads
+            let accessed = chain.execute(tx, false, true);
+            let accessed = slots(accessed.into_iter().map(|(account, state)| {
+                (account, state.storage.into_keys().map(B256::from).collect())
+            }));
+            let declared = slots(
+                tx["accessList"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|entry| (address(&entry["address"]), storage_keys(entry))),
+            );
+            assert_eq!(declared, accessed, "access list of {}", tx["data"]);
+            chain.execute(tx, true, false)
+        })
+        .collect()
+}
+
+/// Returns the slots a transaction wrote, from the state it touched
+fn written(state: &EvmState) -> Slots {
+    slots(state.iter().map(|(account, state)| {
+        let changed = state.changed_storage_slots();
+        let changed = changed.map(|(slot, _)| B256::from(*slot));
+        (*account, changed.collect())
+    }))
+}
+
+fn slot(index: u64) -> B256 {
+    B256::from(U256::from(index))
+}
+
+#[test]
+fn test_amm_swaps_in_evm() {
+    let (pre, transactions) = generate_precise(101, "amm", 50, |generator| {
+        generator.set_skew(SkewConfig {
+            accounts: Some(10),
+            pairs: 2,
+            ..SkewConfig::default()
+        })
+    });
+    for (tx, state) in transactions
+        .iter()
+        .zip(execute_precise(&pre, &transactions))
+    {
+        let written = written(&state);
+        // the pair updates its reserves, both tokens the balances of the trader and the pair
+        let pair = address(&tx["to"]);
+        assert_eq!(written[&pair], BTreeSet::from([slot(2), slot(3)]));
+        assert_eq!(written.len(), 3);
+        assert!(written.values().all(|slots| slots.len() == 2));
+    }
+}
+
+#[test]
+fn test_nft_patterns_in_evm() {
+    // slots written by each call: mint writes the token counter, the owner of the new
+    // token and the balance of the minter, transfers the owner and both balances, and
+    // clear the approval a preceding approve wrote
+    for (pattern, writes) in [
+        ("nft-mint", [3, 3]),
+        ("nft-transfer", [3, 3]),
+        ("nft-approve", [1, 4]),
+    ] {
+        let (pre, transactions) = generate_precise(102, pattern, 20, |generator| {
+            generator.set_skew(SkewConfig {
+                accounts: Some(10),
+                ..SkewConfig::default()
+            })
+        });
+        let contract = address(&transactions[0]["to"]);
+        for (idx, state) in execute_precise(&pre, &transactions).iter().enumerate() {
+            let written = written(state);
+            assert_eq!(written.keys().collect::<Vec<_>>(), [&contract]);
+            assert_eq!(written[&contract].len(), writes[idx % 2], "{}", pattern);
+            if pattern == "nft-mint" {
+                assert!(written[&contract].contains(&slot(0)));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_erc1155_batch_in_evm() {
+    let (pre, transactions) = generate_precise(103, "erc1155-batch", 20, |generator| {
+        generator.set_skew(SkewConfig {
+            accounts: Some(10),
+            ..SkewConfig::default()
+        });
+        generator.set_batch(BatchConfig {
+            batch_size: 4,
+            id_overlap: 0.5,
+        });
+    });
+    let contract = address(&transactions[0]["to"]);
+    for state in execute_precise(&pre, &transactions) {
+        let written = written(&state);
+        // the balances of the sender and the receiver of every id in the batch
+        assert_eq!(written.keys().collect::<Vec<_>>(), [&contract]);
+        assert_eq!(written[&contract].len(), 2 * 4);
+    }
+}
+
+#[test]
+fn test_deploy_patterns_in_evm() {
+    // the runtime code of the new contract, if not padding, and the slots its constructor
+    // writes: the ERC20 constructor mints a balance to the deployer
+    for (pattern, template, code_size, runtime, writes) in [
+        (
+            "deploy",
+            ContractTemplate::Erc20,
+            None,
+            Some(ERC20_USDC_DEPLOYED_BYTECODE),
+            1,
+        ),
+        (
+            "deploy",
+            ContractTemplate::Erc721,
+            None,
+            Some(ERC721_DEPLOYED_BYTECODE),
+            0,
+        ),
+        (
+            "create2",
+            ContractTemplate::Erc20,
+            None,
+            Some(ERC20_USDC_DEPLOYED_BYTECODE),
+            1,
+        ),
+        ("create2", ContractTemplate::Empty, Some(1000), None, 0),
+    ] {
+        let (pre, transactions) = generate_precise(104, pattern, 5, |generator| {
+            generator.set_deploy(DeployConfig {
+                template,
+                code_size,
+            })
+        });
+        for state in execute_precise(&pre, &transactions) {
+            let created: Vec<_> = state
+                .iter()
+                .filter(|(account, _)| !pre.contains_key(&format!("{:?}", account)))
+                .filter(|(_, account)| account.info.code.as_ref().is_some_and(|c| !c.is_empty()))
+                .collect();
+            assert_eq!(created.len(), 1, "{}", pattern);
+            let (contract, account) = created[0];
+            let code = account.info.code.as_ref().unwrap().original_bytes();
+            match runtime {
+                Some(runtime) => assert_eq!(code, runtime.parse::<Bytes>().unwrap()),
+                None => assert!(code.len() == 1000 && code[0] == 0x00),
+            }
+            let written = written(&state);
+            assert!(written.keys().all(|account| account == contract));
+            assert_eq!(written.get(contract).map_or(0, BTreeSet::len), writes);
+        }
+    }
+}
+
+#[test]
+fn test_storage_pattern_in_evm() {
+    let (pre, transactions) = generate_precise(105, "storage", 20, |generator| {
+        generator.set_storage(StorageConfig {
+            slots_per_tx: 12,
+            write_ratio: 0.25,
+            key_overlap: 0.5,
+        })
+    });
+    let contract = address(&transactions[0]["to"]);
+    for (tx, state) in transactions
+        .iter()
+        .zip(execute_precise(&pre, &transactions))
+    {
+        // the calldata words with the top bit set are the slots written
+        let data = bytes(&tx["data"]);
+        let writes: BTreeSet<B256> = data
+            .chunks(32)
+            .filter(|word| word[0] & 0x80 != 0)
+            .map(|word| {
+                let mut slot = B256::from_slice(word);
+                slot[0] &= 0x7f;
+                slot
+            })
+            .collect();
+        assert_eq!(writes.len(), 3);
+        assert_eq!(written(&state), Slots::from([(contract, writes)]));
+    }
+}
+
+#[test]
+fn test_set_code_pattern_in_evm() {
+    // authorities drawn from a small pool, delegated several times over
+    let (pre, transactions) = generate_precise(106, "set-code", 20, |generator| {
+        generator.set_skew(SkewConfig {
+            accounts: Some(4),
+            ..SkewConfig::default()
+        });
+        generator.set_set_code(SetCodeConfig {
+            authorizations_per_tx: 2,
+            call_ratio: 0.5,
+        });
+    });
+    let delegate = address(&transactions[0]["authorizationList"][0]["address"]);
+    for (tx, state) in transactions
+        .iter()
+        .zip(execute_precise(&pre, &transactions))
+    {
+        // the delegation is in effect when the called account runs: it increments the
+        // counter in its own storage
+        let to = address(&tx["to"]);
+        assert_eq!(
+            written(&state),
+            Slots::from([(to, BTreeSet::from([slot(0)]))])
+        );
+        if let Some(authorizations) = tx["authorizationList"].as_array() {
+            for authorization in authorizations {
+                let authority = address(&authorization["signer"]);
+                let code = state[&authority].info.code.as_ref().unwrap();
+                assert_eq!(
+                    code.original_bytes(),
+                    Bytecode::new_eip7702(delegate).original_bytes()
+                );
+            }
+        }
+    }
+}
+
+#[cfg(feature = "blob")]
+#[test]
+fn test_blob_pattern_in_evm() {
+    use altius_benchtools::{BlobConfig, FeeDistribution};
+
+    // fees far above the usual balance of the senders, which can only pay for their
+    // transactions if `pre` funds both the execution and the blob gas
+    let fee = FeeDistribution::Fixed(1_000_000_000_000_000);
+    let (pre, transactions) = generate_precise(107, "blob", 6, |generator| {
+        generator.set_skew(SkewConfig {
+            accounts: Some(2),
+            ..SkewConfig::default()
+        });
+        generator.set_fees(FeeConfig {
+            tx_type: TxType::DynamicFee,
+            gas_price: fee,
+            priority_fee: FeeDistribution::Fixed(1_000_000_000),
+        });
+        generator.set_blobs(BlobConfig {
+            blobs_per_tx: 1..=2,
+            max_fee_per_blob_gas: fee,
+            sidecars: false,
+        });
+    });
+    let mut max_costs: BTreeMap<Address, U256> = BTreeMap::new();
+    for tx in &transactions {
+        let blob_gas = 131_072 * tx["blobVersionedHashes"].as_array().unwrap().len() as u64;
+        let max_cost = quantity(&tx["gasLimit"]) * quantity(&tx["maxFeePerGas"])
+            + U256::from(blob_gas) * quantity(&tx["maxFeePerBlobGas"]);
+        *max_costs.entry(address(&tx["sender"])).or_default() += max_cost;
+    }
+    // on top of the usual 100 ether, not enough for a single transaction
+    let usual_balance = U256::from(100_000_000_000_000_000_000u128);
+    for (sender, max_cost) in max_costs {
+        let balance = quantity(&pre[&format!("{:?}", sender)]["balance"]);
+        assert!(max_cost > usual_balance);
+        assert_eq!(balance, usual_balance + max_cost);
+    }
+
+    for state in execute_precise(&pre, &transactions) {
+        assert!(written(&state).is_empty());
+    }
+}